@@ -0,0 +1,60 @@
+use std::str::FromStr;
+
+use anyhow::Context;
+
+pub const INPUT: &str = include_str!("input.txt");
+
+/// Sea floor depths from a sonar sweep, the n-th reading lies at horizontal position n.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DepthProfile {
+    depths: Vec<usize>,
+}
+
+impl DepthProfile {
+    pub fn new(depths: Vec<usize>) -> Self {
+        Self { depths }
+    }
+
+    pub fn depths(&self) -> &[usize] {
+        &self.depths
+    }
+
+    /// The sea floor depth at `horizontal`, `None` beyond the sweep.
+    pub fn depth_at(&self, horizontal: usize) -> Option<usize> {
+        self.depths.get(horizontal).copied()
+    }
+}
+
+/// Parses one reading per line, empty lines are skipped.
+impl FromStr for DepthProfile {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.lines()
+            .enumerate()
+            .filter(|(_, line)| !line.is_empty())
+            .map(|(index, line)| {
+                line.parse::<usize>()
+                    .with_context(|| format!("Line {}: {:?}", index + 1, line))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()
+            .map(DepthProfile::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn test_parse_profile() {
+        let profile = "199\n200\n208\n".parse::<DepthProfile>().unwrap();
+
+        assert_eq!(&[199, 200, 208], profile.depths());
+        assert_eq!(Some(199), profile.depth_at(0));
+        assert_eq!(None, profile.depth_at(3));
+
+        let error = "199\n2OO\n".parse::<DepthProfile>().unwrap_err();
+        assert!(error.to_string().starts_with("Line 2:"));
+    }
+}
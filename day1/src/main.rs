@@ -1,9 +1,12 @@
 use itertools::Itertools;
 
+use day1::{DepthProfile, INPUT};
+
 fn main() -> anyhow::Result<()> {
-    let result = include_str!("input.txt")
-        .split('\n')
-        .filter_map(|s| s.parse::<usize>().ok())
+    let result = INPUT
+        .parse::<DepthProfile>()?
+        .depths()
+        .iter()
         .tuple_windows()
         .map(|(a, b, c)| a + b + c)
         .fold((0_usize, usize::MAX), |acc, v| {
@@ -0,0 +1,74 @@
+use anyhow::{bail, Context};
+
+use day1::DepthProfile;
+use day2::*;
+
+/// Usage: `grounding [simple|aim] [--clamp-surface] [<sonar sweep> <course>]`.
+/// Sails the course over the sonar sweep and reports where the submarine goes below the sea floor.
+/// The aim model and the day1 and day2 inputs are used by default.
+fn main() -> anyhow::Result<()> {
+    let mut model: &dyn NavigationModel = &AimModel;
+    let mut surface = SurfaceRule::Ignore;
+    let mut paths = Vec::new();
+
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--clamp-surface" => surface = SurfaceRule::Clamp,
+            "simple" | "aim" => model = select_model(&arg)?,
+            _ => paths.push(arg),
+        }
+    }
+
+    let (sweep, course) = match &paths[..] {
+        [] => (day1::INPUT.to_string(), day2::INPUT.to_string()),
+        [sweep, course] => (read(sweep)?, read(course)?),
+        _ => bail!("Expected both the sonar sweep and the course, or neither"),
+    };
+
+    let profile = sweep.parse::<DepthProfile>()?;
+    let commands = parse_commands(&course)?;
+    let report = sound_course(&profile, model, &commands, surface)?;
+
+    println!(
+        "{} model, {} positions sounded",
+        model.name(),
+        report.soundings.len()
+    );
+
+    if report.leaves_sweep {
+        println!(
+            "The course leaves the sweep after horizontal {}",
+            profile.depths().len().saturating_sub(1)
+        );
+    }
+
+    match report.groundings().next() {
+        Some(first) => {
+            let command = first
+                .index
+                .checked_sub(1)
+                .and_then(|i| commands.get(i))
+                .map_or(String::new(), |c| c.to_string());
+
+            println!(
+                "Grounded first at horizontal {} by command {} ({}): depth {}, sea floor {}",
+                first.position.horizontal,
+                first.index,
+                command,
+                first.position.depth,
+                first.sea_floor
+            );
+
+            for range in report.grounded_ranges() {
+                println!("Below the sea floor at {}..={}", range.start(), range.end());
+            }
+        }
+        None => println!("Never below the sea floor"),
+    }
+
+    Ok(())
+}
+
+fn read(path: &str) -> anyhow::Result<String> {
+    std::fs::read_to_string(path).with_context(|| format!("Cannot read {:?}", path))
+}
@@ -0,0 +1,87 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, Context};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Command {
+    Forward(usize),
+    Up(usize),
+    Down(usize),
+}
+
+impl FromStr for Command {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (direction, value) = s
+            .split_once(' ')
+            .ok_or_else(|| anyhow!("Expected `<direction> <value>`, got {:?}", s))?;
+
+        let value = value
+            .parse::<usize>()
+            .with_context(|| format!("Invalid value {:?}", value))?;
+
+        match direction {
+            "forward" => Ok(Command::Forward(value)),
+            "up" => Ok(Command::Up(value)),
+            "down" => Ok(Command::Down(value)),
+            _ => bail!("Unknown direction {:?}", direction),
+        }
+    }
+}
+
+impl Display for Command {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Command::Forward(value) => write!(f, "forward {}", value),
+            Command::Up(value) => write!(f, "up {}", value),
+            Command::Down(value) => write!(f, "down {}", value),
+        }
+    }
+}
+
+impl Command {
+    pub fn value(&self) -> usize {
+        match *self {
+            Command::Forward(value) | Command::Up(value) | Command::Down(value) => value,
+        }
+    }
+}
+
+/// Parses one command per line, empty lines are skipped.
+/// The error of the first malformed line reports its 1-based number.
+pub fn parse_commands(input: &str) -> anyhow::Result<Vec<Command>> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(index, line)| {
+            line.parse::<Command>()
+                .with_context(|| format!("Line {}: {:?}", index + 1, line))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::command::*;
+
+    #[test]
+    fn test_parse_command() {
+        assert_eq!(Command::Forward(5), "forward 5".parse::<Command>().unwrap());
+        assert_eq!(Command::Up(3), "up 3".parse::<Command>().unwrap());
+        assert_eq!(Command::Down(8), "down 8".parse::<Command>().unwrap());
+
+        assert!("forwrd 5".parse::<Command>().is_err());
+        assert!("forward".parse::<Command>().is_err());
+        assert!("forward five".parse::<Command>().is_err());
+        assert!("forward -5".parse::<Command>().is_err());
+    }
+
+    #[test]
+    fn test_parse_commands_reports_line() {
+        let error = parse_commands("forward 5\nforwrd 5\n").unwrap_err();
+        assert!(error.to_string().starts_with("Line 2:"));
+    }
+}
@@ -0,0 +1,119 @@
+use std::io::Write;
+
+use crate::{Position, TrajectoryPoint};
+
+pub fn write_csv(trajectory: &[TrajectoryPoint], out: &mut impl Write) -> std::io::Result<()> {
+    writeln!(out, "index,command,horizontal,depth,aim")?;
+
+    for point in trajectory {
+        writeln!(
+            out,
+            "{},{},{},{},{}",
+            point.index,
+            point.command,
+            point.position.horizontal,
+            point.position.depth,
+            point.position.aim
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Plots the course as horizontal position against depth, depth grows downwards.
+/// Every point is marked with a circle whose tooltip names the command.
+pub fn write_svg(trajectory: &[TrajectoryPoint], out: &mut impl Write) -> std::io::Result<()> {
+    const WIDTH: f64 = 800.0;
+    const HEIGHT: f64 = 600.0;
+    const MARGIN: f64 = 10.0;
+
+    let start = Position::default();
+    let positions = std::iter::once(&start).chain(trajectory.iter().map(|p| &p.position));
+
+    let (min_x, max_x, min_y, max_y) = positions.clone().fold(
+        (0_i64, 0_i64, 0_i64, 0_i64),
+        |(min_x, max_x, min_y, max_y), p| {
+            (
+                min_x.min(p.horizontal),
+                max_x.max(p.horizontal),
+                min_y.min(p.depth),
+                max_y.max(p.depth),
+            )
+        },
+    );
+
+    let scale = |value: i64, min: i64, max: i64, size: f64| {
+        let span = (max - min).max(1) as f64;
+        MARGIN + (value - min) as f64 / span * (size - 2.0 * MARGIN)
+    };
+    let x = |p: &Position| scale(p.horizontal, min_x, max_x, WIDTH);
+    let y = |p: &Position| scale(p.depth, min_y, max_y, HEIGHT);
+
+    writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}">"#,
+        WIDTH, HEIGHT
+    )?;
+
+    write!(out, r#"<path fill="none" stroke="black" d=""#)?;
+    for (i, p) in positions.enumerate() {
+        let op = if i == 0 { 'M' } else { 'L' };
+        write!(out, "{}{:.2},{:.2} ", op, x(p), y(p))?;
+    }
+    writeln!(out, r#""/>"#)?;
+
+    for point in trajectory {
+        let p = &point.position;
+        writeln!(
+            out,
+            r#"<circle cx="{:.2}" cy="{:.2}" r="2"><title>#{} {}: horizontal {}, depth {}, aim {}</title></circle>"#,
+            x(p),
+            y(p),
+            point.index,
+            point.command,
+            p.horizontal,
+            p.depth,
+            p.aim
+        )?;
+    }
+
+    writeln!(out, "</svg>")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::export::*;
+    use crate::{parse_commands, AimModel, NavigationModel, SurfaceRule, TEST_DATA};
+
+    #[test]
+    fn test_write_csv() {
+        let commands = parse_commands("forward 5\ndown 5\nforward 8\n").unwrap();
+        let trajectory = AimModel.trajectory(&commands, SurfaceRule::Ignore).unwrap();
+
+        let mut out = Vec::new();
+        write_csv(&trajectory, &mut out).unwrap();
+
+        assert_eq!(
+            "index,command,horizontal,depth,aim\n\
+             1,forward 5,5,0,0\n\
+             2,down 5,5,0,5\n\
+             3,forward 8,13,40,5\n",
+            String::from_utf8(out).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_write_svg() {
+        let commands = parse_commands(TEST_DATA).unwrap();
+        let trajectory = AimModel.trajectory(&commands, SurfaceRule::Ignore).unwrap();
+
+        let mut out = Vec::new();
+        write_svg(&trajectory, &mut out).unwrap();
+        let svg = String::from_utf8(out).unwrap();
+
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        assert_eq!(6, svg.matches("<circle").count());
+        assert!(svg.contains("<title>#6 forward 2: horizontal 15, depth 60, aim 10</title>"));
+    }
+}
@@ -0,0 +1,16 @@
+mod command;
+mod export;
+mod navigation;
+mod planner;
+mod sonar;
+
+pub use command::*;
+pub use export::*;
+pub use navigation::*;
+pub use planner::*;
+pub use sonar::*;
+
+pub const INPUT: &str = include_str!("input.txt");
+
+#[cfg(test)]
+const TEST_DATA: &str = "forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2\n";
@@ -1,20 +1,105 @@
+use anyhow::{anyhow, bail, Context};
+
+use day2::*;
+
+/// Usage: `day2 [simple|aim] [--clamp-surface] [--csv|--svg]`.
+/// Both models are evaluated when none is given.
+/// `--csv` and `--svg` print the trajectory of a single selected model instead of the answer.
+///
+/// `day2 plan <horizontal> <depth> [--max-forward N] [--max-aim N]` prints the shortest course
+/// to the target under the aim model instead.
 fn main() -> anyhow::Result<()> {
-    let positions = include_str!("input.txt")
-    .split('\n')
-    .filter_map(|line| {
-        let mut s = line.split(' ');
-        let direction = s.next()?;
-        let value = s.next()?.parse::<usize>().ok()?;
-       Some((direction, value))}
-    )
-    .fold((0_usize, 0_usize, 0_usize), |(hor, ver, aim), (direction, value)| match direction {
-        "forward" => (hor + value, ver + aim * value, aim),
-        "up" => (hor, ver, aim - value),
-        "down" => (hor, ver, aim + value),
-        _ => (hor, ver, aim)
-    });
-
-    println!("{}", positions.0 * positions.1);
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+
+    if args.first().map(String::as_str) == Some("plan") {
+        return run_plan(&args[1..]);
+    }
+
+    let mut models: Vec<&dyn NavigationModel> = Vec::new();
+    let mut surface = SurfaceRule::Ignore;
+    let mut export: Option<Export> = None;
+
+    for arg in args {
+        match arg.as_str() {
+            "--clamp-surface" => surface = SurfaceRule::Clamp,
+            "--csv" => export = Some(Export::Csv),
+            "--svg" => export = Some(Export::Svg),
+            name => models.push(select_model(name)?),
+        }
+    }
+
+    let commands = parse_commands(INPUT)?;
+
+    if let Some(export) = export {
+        let model = match models[..] {
+            [model] => model,
+            _ => bail!("Select exactly one model to export its trajectory"),
+        };
+
+        let trajectory = model.trajectory(&commands, surface)?;
+        let mut out = std::io::stdout().lock();
+        match export {
+            Export::Csv => write_csv(&trajectory, &mut out)?,
+            Export::Svg => write_svg(&trajectory, &mut out)?,
+        }
+
+        return Ok(());
+    }
+
+    if models.is_empty() {
+        models = vec![&SimpleModel, &AimModel];
+    }
+
+    for model in models {
+        let position = model.navigate(&commands, surface)?;
+        println!("{} model: {}", model.name(), position.product()?);
+    }
 
     Ok(())
 }
+
+fn run_plan(args: &[String]) -> anyhow::Result<()> {
+    let mut args = args.iter();
+    let mut target = Vec::new();
+    let mut limits = Limits::default();
+
+    while let Some(arg) = args.next() {
+        let mut limit = || -> anyhow::Result<Option<usize>> {
+            let value = args
+                .next()
+                .ok_or_else(|| anyhow!("Missing value for {}", arg))?;
+            Ok(Some(value.parse().with_context(|| {
+                format!("Invalid value {:?} for {}", value, arg)
+            })?))
+        };
+
+        match arg.as_str() {
+            "--max-forward" => limits.forward = limit()?,
+            "--max-aim" => limits.aim = limit()?,
+            value => target.push(
+                value
+                    .parse::<i64>()
+                    .with_context(|| format!("Invalid coordinate {:?}", value))?,
+            ),
+        }
+    }
+
+    let (horizontal, depth) = match target[..] {
+        [horizontal, depth] => (horizontal, depth),
+        _ => bail!("Usage: day2 plan <horizontal> <depth> [--max-forward N] [--max-aim N]"),
+    };
+
+    let course = plan_course(horizontal, depth, limits)
+        .ok_or_else(|| anyhow!("({}, {}) is unreachable", horizontal, depth))?;
+
+    for command in course {
+        println!("{}", command);
+    }
+
+    Ok(())
+}
+
+enum Export {
+    Csv,
+    Svg,
+}
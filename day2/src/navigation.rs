@@ -0,0 +1,276 @@
+use anyhow::{anyhow, bail};
+
+use crate::Command;
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct Position {
+    pub horizontal: i64,
+    pub depth: i64,
+    pub aim: i64,
+}
+
+impl Position {
+    pub fn new(horizontal: i64, depth: i64, aim: i64) -> Self {
+        Self {
+            horizontal,
+            depth,
+            aim,
+        }
+    }
+
+    pub fn product(&self) -> anyhow::Result<i64> {
+        self.horizontal
+            .checked_mul(self.depth)
+            .ok_or_else(|| anyhow!("Overflow in {} * {}", self.horizontal, self.depth))
+    }
+}
+
+/// What happens when a course takes the submarine above depth 0.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SurfaceRule {
+    /// Depth goes negative.
+    Ignore,
+    /// Depth stays at 0, the submarine cannot leave the water.
+    Clamp,
+}
+
+impl SurfaceRule {
+    pub fn constrain(&self, position: Position) -> Position {
+        match self {
+            SurfaceRule::Ignore => position,
+            SurfaceRule::Clamp => Position {
+                depth: position.depth.max(0),
+                ..position
+            },
+        }
+    }
+}
+
+pub trait NavigationModel {
+    fn name(&self) -> &'static str;
+    fn apply(&self, position: Position, command: Command) -> Option<Position>;
+
+    /// Applies the command at the 1-based `index` and the surface rule.
+    fn step(
+        &self,
+        position: Position,
+        index: usize,
+        command: Command,
+        surface: SurfaceRule,
+    ) -> anyhow::Result<Position> {
+        self.apply(position, command)
+            .map(|position| surface.constrain(position))
+            .ok_or_else(|| {
+                anyhow!(
+                    "Overflow at command {} ({:?}) from {:?}",
+                    index,
+                    command,
+                    position
+                )
+            })
+    }
+
+    fn navigate(&self, commands: &[Command], surface: SurfaceRule) -> anyhow::Result<Position> {
+        commands
+            .iter()
+            .enumerate()
+            .try_fold(Position::default(), |position, (index, &command)| {
+                self.step(position, index + 1, command, surface)
+            })
+    }
+
+    /// Returns the position after every command.
+    fn trajectory(
+        &self,
+        commands: &[Command],
+        surface: SurfaceRule,
+    ) -> anyhow::Result<Vec<TrajectoryPoint>> {
+        let mut position = Position::default();
+
+        commands
+            .iter()
+            .enumerate()
+            .map(|(index, &command)| {
+                position = self.step(position, index + 1, command, surface)?;
+                Ok(TrajectoryPoint {
+                    index: index + 1,
+                    command,
+                    position,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Part 1: `up` and `down` change the depth directly.
+pub struct SimpleModel;
+
+/// Part 2: `up` and `down` change the aim, `forward` dives along it.
+pub struct AimModel;
+
+impl NavigationModel for SimpleModel {
+    fn name(&self) -> &'static str {
+        "simple"
+    }
+
+    fn apply(&self, p: Position, command: Command) -> Option<Position> {
+        let value = i64::try_from(command.value()).ok()?;
+
+        match command {
+            Command::Forward(_) => Some(Position::new(
+                p.horizontal.checked_add(value)?,
+                p.depth,
+                p.aim,
+            )),
+            Command::Up(_) => Some(Position::new(
+                p.horizontal,
+                p.depth.checked_sub(value)?,
+                p.aim,
+            )),
+            Command::Down(_) => Some(Position::new(
+                p.horizontal,
+                p.depth.checked_add(value)?,
+                p.aim,
+            )),
+        }
+    }
+}
+
+impl NavigationModel for AimModel {
+    fn name(&self) -> &'static str {
+        "aim"
+    }
+
+    fn apply(&self, p: Position, command: Command) -> Option<Position> {
+        let value = i64::try_from(command.value()).ok()?;
+
+        match command {
+            Command::Forward(_) => Some(Position::new(
+                p.horizontal.checked_add(value)?,
+                p.depth.checked_add(p.aim.checked_mul(value)?)?,
+                p.aim,
+            )),
+            Command::Up(_) => Some(Position::new(
+                p.horizontal,
+                p.depth,
+                p.aim.checked_sub(value)?,
+            )),
+            Command::Down(_) => Some(Position::new(
+                p.horizontal,
+                p.depth,
+                p.aim.checked_add(value)?,
+            )),
+        }
+    }
+}
+
+pub fn select_model(name: &str) -> anyhow::Result<&'static dyn NavigationModel> {
+    match name {
+        "simple" => Ok(&SimpleModel),
+        "aim" => Ok(&AimModel),
+        _ => bail!(
+            "Unknown navigation model {:?}, expected `simple` or `aim`",
+            name
+        ),
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct TrajectoryPoint {
+    /// 1-based index of the command in the course.
+    pub index: usize,
+    pub command: Command,
+    /// Position after the command.
+    pub position: Position,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::navigation::*;
+    use crate::{parse_commands, TEST_DATA};
+
+    #[test]
+    fn test_simple_model() {
+        let commands = parse_commands(TEST_DATA).unwrap();
+        assert_eq!(
+            Position::new(15, 10, 0),
+            SimpleModel
+                .navigate(&commands, SurfaceRule::Ignore)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_aim_model() {
+        let commands = parse_commands(TEST_DATA).unwrap();
+        assert_eq!(
+            Position::new(15, 60, 10),
+            AimModel.navigate(&commands, SurfaceRule::Ignore).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_select_model() {
+        assert_eq!("simple", select_model("simple").unwrap().name());
+        assert_eq!("aim", select_model("aim").unwrap().name());
+        assert!(select_model("other").is_err());
+    }
+
+    #[test]
+    fn test_above_surface() {
+        let commands = parse_commands("up 5\nforward 2\n").unwrap();
+
+        assert_eq!(
+            Position::new(2, -5, 0),
+            SimpleModel
+                .navigate(&commands, SurfaceRule::Ignore)
+                .unwrap()
+        );
+        assert_eq!(
+            Position::new(2, -10, -5),
+            AimModel.navigate(&commands, SurfaceRule::Ignore).unwrap()
+        );
+        assert_eq!(
+            Position::new(2, 0, 0),
+            SimpleModel.navigate(&commands, SurfaceRule::Clamp).unwrap()
+        );
+        assert_eq!(
+            Position::new(2, 0, -5),
+            AimModel.navigate(&commands, SurfaceRule::Clamp).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_overflow() {
+        let commands = [Command::Down(i64::MAX as usize), Command::Forward(2)];
+        let error = AimModel
+            .navigate(&commands, SurfaceRule::Ignore)
+            .unwrap_err();
+        assert!(error.to_string().starts_with("Overflow at command 2"));
+
+        let commands = [Command::Up(usize::MAX)];
+        assert!(SimpleModel
+            .navigate(&commands, SurfaceRule::Ignore)
+            .is_err());
+    }
+
+    #[test]
+    fn test_trajectory() {
+        let commands = parse_commands(TEST_DATA).unwrap();
+        let trajectory = AimModel.trajectory(&commands, SurfaceRule::Ignore).unwrap();
+
+        assert_eq!(6, trajectory.len());
+        assert_eq!(
+            TrajectoryPoint {
+                index: 3,
+                command: Command::Forward(8),
+                position: Position::new(13, 40, 5)
+            },
+            trajectory[2]
+        );
+        assert_eq!(
+            AimModel.navigate(&commands, SurfaceRule::Ignore).unwrap(),
+            trajectory.last().unwrap().position
+        );
+    }
+}
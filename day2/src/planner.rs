@@ -0,0 +1,404 @@
+use std::collections::HashMap;
+
+use crate::Command;
+
+/// Upper bounds for the values of planned commands, `None` means unbounded.
+/// The aim limit applies to both `up` and `down`.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct Limits {
+    pub forward: Option<usize>,
+    pub aim: Option<usize>,
+}
+
+/// Finds the shortest course that takes the submarine from the origin to `(horizontal, depth)`
+/// under the aim model, `None` if no course reaches the target or its length does not fit
+/// in an `i64`.
+pub fn plan_course(horizontal: i64, depth: i64, limits: Limits) -> Option<Vec<Command>> {
+    if horizontal < 0 || (horizontal == 0 && depth != 0) {
+        return None;
+    }
+
+    // A value as large as the target itself is never a restriction.
+    let as_limit = |limit: Option<usize>, unbounded: i64| match limit {
+        Some(0) => None,
+        Some(limit) => Some(i64::try_from(limit).unwrap_or(i64::MAX).min(unbounded)),
+        None => Some(unbounded),
+    };
+
+    let forward = match as_limit(limits.forward, horizontal.max(1)) {
+        Some(forward) => forward,
+        None if horizontal == 0 => 1,
+        None => return None,
+    };
+    let aim = match as_limit(limits.aim, depth.checked_abs()?.max(1)) {
+        Some(aim) => aim,
+        None if depth == 0 => 1,
+        None => return None,
+    };
+
+    let mut planner = CoursePlanner {
+        forward,
+        aim,
+        exhausted: HashMap::new(),
+    };
+
+    // Forwards alone need this many commands, and every aim command adds at most
+    // `aim * horizontal` to the depth.
+    let mut budget = div_ceil(horizontal, forward)
+        .checked_add(div_ceil(depth.abs(), aim.saturating_mul(horizontal.max(1))))?;
+
+    loop {
+        let mut course = Vec::new();
+        if planner.search(horizontal, depth, budget, true, &mut course) {
+            return Some(course);
+        }
+        budget = budget.checked_add(1)?;
+    }
+}
+
+/// Depth-first search over courses of a fixed length.
+///
+/// Every `up`/`down` run changes the depth of the remaining course by `change * remaining`,
+/// where `remaining` is the horizontal distance still to travel. So a course is a series of
+/// aim changes placed at strictly decreasing `remaining` values, joined by forward runs.
+/// Each run is split into as few commands as the limits allow.
+struct CoursePlanner {
+    forward: i64,
+    aim: i64,
+    /// The largest budget known to be insufficient for `(remaining, depth, here)`.
+    exhausted: HashMap<(i64, i64, bool), i64>,
+}
+
+impl CoursePlanner {
+    /// Looks for a course of at most `budget` commands that travels `remaining` forward and
+    /// changes the depth by `depth`. `here` allows an aim change before the first forward.
+    fn search(
+        &mut self,
+        remaining: i64,
+        depth: i64,
+        budget: i64,
+        here: bool,
+        course: &mut Vec<Command>,
+    ) -> bool {
+        if depth == 0 {
+            if div_ceil(remaining, self.forward) > budget {
+                return false;
+            }
+            self.push_forward(course, remaining);
+            return true;
+        }
+
+        let key = (remaining, depth, here);
+        if self.exhausted.get(&key).is_some_and(|&b| budget <= b) {
+            return false;
+        }
+
+        let first = if here { remaining } else { remaining - 1 };
+
+        // Finish with a single aim change when the depth divides evenly.
+        for at in divisors(depth, first) {
+            let aim_budget = self.aim_budget(remaining, at, budget);
+            if div_ceil((depth / at).abs(), self.aim) <= aim_budget {
+                self.push_forward(course, remaining - at);
+                self.push_aim(course, depth / at);
+                self.push_forward(course, at);
+                return true;
+            }
+        }
+
+        // Splitting the forwards never saves commands, so there is no room for two aim changes.
+        if budget - div_ceil(remaining, self.forward) < 2 {
+            self.exhausted.insert(key, budget);
+            return false;
+        }
+
+        // Otherwise change the aim by `change` here and leave the rest to the later changes.
+        for at in (2..=first).rev() {
+            let aim_budget = self.aim_budget(remaining, at, budget);
+            if aim_budget < 2 || depth.abs() > self.max_depth(aim_budget, at) {
+                continue;
+            }
+
+            for cost in (1..aim_budget).rev() {
+                let rest = self.max_depth(aim_budget - cost, at - 1);
+                let low = div_ceil(depth.saturating_sub(rest), at);
+                let high = depth.saturating_add(rest).div_euclid(at);
+                let (least, most) = ((cost - 1) * self.aim + 1, cost.saturating_mul(self.aim));
+                let bands = [
+                    (low.max(-most), high.min(-least)),
+                    (low.max(least), high.min(most)),
+                ];
+
+                for change in bands
+                    .into_iter()
+                    .flat_map(|(lo, hi)| candidates(depth, at, lo, hi))
+                {
+                    let len = course.len();
+                    self.push_forward(course, remaining - at);
+                    self.push_aim(course, change);
+
+                    let forward_cost = div_ceil(remaining - at, self.forward);
+                    let budget = budget - forward_cost - cost;
+                    if self.search(at, depth - change * at, budget, false, course) {
+                        return true;
+                    }
+
+                    course.truncate(len);
+                }
+            }
+        }
+
+        self.exhausted.insert(key, budget);
+        false
+    }
+
+    /// Commands left for aim changes after moving from `remaining` to `at` and on to the end.
+    fn aim_budget(&self, remaining: i64, at: i64, budget: i64) -> i64 {
+        budget - div_ceil(remaining - at, self.forward) - div_ceil(at, self.forward)
+    }
+
+    /// The largest depth change `commands` aim changes can make with at most `at` to travel.
+    fn max_depth(&self, commands: i64, at: i64) -> i64 {
+        commands.saturating_mul(self.aim).saturating_mul(at)
+    }
+
+    fn push_forward(&self, course: &mut Vec<Command>, mut distance: i64) {
+        while distance > 0 {
+            let value = distance.min(self.forward);
+            course.push(Command::Forward(value as usize));
+            distance -= value;
+        }
+    }
+
+    fn push_aim(&self, course: &mut Vec<Command>, change: i64) {
+        let mut left = change.abs();
+        while left > 0 {
+            let value = left.min(self.aim) as usize;
+            course.push(if change > 0 {
+                Command::Down(value)
+            } else {
+                Command::Up(value)
+            });
+            left -= value as i64;
+        }
+    }
+}
+
+/// The changes at `at` worth trying among `lo..=hi`, which all cost the same.
+///
+/// Stepping the change moves the remainder left for the later changes by `at`, and those
+/// only reach remainders of some residue modulo their own positions, which are below `at`.
+/// So every residue shows up within `at` steps of the ends of the range and of the change
+/// that leaves the smallest remainder, and the changes further in never do better.
+fn candidates(depth: i64, at: i64, lo: i64, hi: i64) -> Vec<i64> {
+    if lo > hi {
+        return Vec::new();
+    }
+
+    let nearest = depth.div_euclid(at).clamp(lo, hi);
+    let mut changes = [
+        (lo, lo.saturating_add(at)),
+        (nearest.saturating_sub(at), nearest.saturating_add(at)),
+        (hi.saturating_sub(at), hi),
+    ]
+    .into_iter()
+    .flat_map(|(from, to)| from.max(lo)..=to.min(hi))
+    .collect::<Vec<_>>();
+
+    changes.sort_unstable();
+    changes.dedup();
+    changes
+}
+
+/// The positive divisors of `value` up to `max`, largest first, in time proportional to the
+/// smaller of `max` and the square root of `value`.
+fn divisors(value: i64, max: i64) -> Vec<i64> {
+    let value = value.unsigned_abs();
+    let max = max.max(0) as u64;
+
+    // Below the square root every candidate is tried directly.
+    if max <= value / max.max(1) {
+        return (1..=max)
+            .rev()
+            .filter(|&divisor| value.is_multiple_of(divisor))
+            .map(|divisor| divisor as i64)
+            .collect();
+    }
+
+    let (mut small, mut large) = (Vec::new(), Vec::new());
+
+    let mut divisor = 1;
+    while divisor <= value / divisor {
+        if value.is_multiple_of(divisor) {
+            small.push(divisor);
+            if divisor != value / divisor && value / divisor <= max {
+                large.push(value / divisor);
+            }
+        }
+        divisor += 1;
+    }
+
+    large
+        .into_iter()
+        .chain(small.into_iter().rev())
+        .map(|divisor| divisor as i64)
+        .collect()
+}
+
+/// Rounds towards positive infinity, `divisor` must be positive.
+fn div_ceil(value: i64, divisor: i64) -> i64 {
+    value.div_euclid(divisor) + i64::from(value.rem_euclid(divisor) != 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::planner::*;
+    use crate::{parse_commands, AimModel, NavigationModel, SurfaceRule};
+
+    #[test]
+    fn test_plan_course_unlimited() {
+        let cases = [
+            ((0, 0), 0),
+            ((15, 0), 1),
+            ((15, 60), 2),
+            ((15, -60), 2),
+            ((15, 61), 3),
+            ((1950, 1_000_003), 3),
+            ((1_000_000_000, 1), 3),
+            ((1_000_000_000, 999_999_937), 3),
+            ((2, 9_223_372_036_854_775_783), 3),
+            ((1_000_000, 1_000_000_000_000_000_003), 3),
+        ];
+
+        for ((horizontal, depth), len) in cases {
+            let course = plan_course(horizontal, depth, Limits::default()).unwrap();
+            let position = AimModel.navigate(&course, SurfaceRule::Ignore).unwrap();
+
+            assert_eq!(len, course.len());
+            assert_eq!((horizontal, depth), (position.horizontal, position.depth));
+        }
+    }
+
+    #[test]
+    fn test_plan_course_unreachable() {
+        assert_eq!(None, plan_course(-1, 0, Limits::default()));
+        assert_eq!(None, plan_course(0, 5, Limits::default()));
+
+        let no_aim = Limits {
+            forward: None,
+            aim: Some(0),
+        };
+        assert_eq!(None, plan_course(5, 5, no_aim));
+        assert!(plan_course(5, 0, no_aim).is_some());
+    }
+
+    #[test]
+    fn test_plan_course_large_aim_limit() {
+        let cases = [
+            ((3, 10_000_000_019), 1_000_000_000, 6),
+            ((10, 100_000_007), 100_000, 103),
+            ((50, 123_456_789), 100_000, 27),
+        ];
+
+        for ((horizontal, depth), aim, len) in cases {
+            let limits = Limits {
+                forward: None,
+                aim: Some(aim),
+            };
+            let course = plan_course(horizontal, depth, limits).unwrap();
+            let position = AimModel.navigate(&course, SurfaceRule::Ignore).unwrap();
+
+            assert_eq!(len, course.len());
+            assert_eq!((horizontal, depth), (position.horizontal, position.depth));
+        }
+    }
+
+    #[test]
+    fn test_plan_course_extreme_target() {
+        assert_eq!(
+            Some(vec![Command::Forward(i64::MAX as usize)]),
+            plan_course(i64::MAX, 0, Limits::default())
+        );
+
+        // Forwards alone take `i64::MAX` commands, one more aim change does not fit.
+        let single_steps = Limits {
+            forward: Some(1),
+            aim: None,
+        };
+        assert_eq!(None, plan_course(i64::MAX, 1, single_steps));
+    }
+
+    #[test]
+    fn test_plan_course_round_trip() {
+        let limits = Limits {
+            forward: Some(9),
+            aim: Some(9),
+        };
+        let course = plan_course(1950, 987_654, limits).unwrap();
+
+        assert!(course.iter().all(|c| (1..=9).contains(&c.value())));
+
+        let text = course
+            .iter()
+            .map(|c| format!("{}\n", c))
+            .collect::<String>();
+        let position = AimModel
+            .navigate(&parse_commands(&text).unwrap(), SurfaceRule::Ignore)
+            .unwrap();
+        assert_eq!((1950, 987_654), (position.horizontal, position.depth));
+    }
+
+    /// Breadth-first search over every reachable state, feasible only for tiny targets.
+    fn shortest_course_len(horizontal: i64, depth: i64, forward: i64, aim: i64) -> usize {
+        let bound = 2 * depth.abs() + 2;
+        let mut seen = std::collections::HashSet::from([(0, 0, 0)]);
+        let mut queue = std::collections::VecDeque::from([((0, 0, 0), 0)]);
+
+        while let Some(((h, d, a), len)) = queue.pop_front() {
+            if (h, d) == (horizontal, depth) {
+                return len;
+            }
+
+            let forwards = (1..=forward.min(horizontal - h)).map(|x| (h + x, d + a * x, a));
+            let aims = (1..=aim).flat_map(|x| [(h, d, a + x), (h, d, a - x)]);
+
+            for next in forwards.chain(aims) {
+                if next.2.abs() <= bound && next.1.abs() <= bound * horizontal && seen.insert(next)
+                {
+                    queue.push_back((next, len + 1));
+                }
+            }
+        }
+
+        unreachable!()
+    }
+
+    #[test]
+    fn test_plan_course_is_shortest() {
+        for forward in 1..=3 {
+            for aim in 1..=3 {
+                let limits = Limits {
+                    forward: Some(forward),
+                    aim: Some(aim),
+                };
+
+                for horizontal in 1..=6 {
+                    for depth in -12..=12 {
+                        let course = plan_course(horizontal, depth, limits).unwrap();
+                        let position = AimModel.navigate(&course, SurfaceRule::Ignore).unwrap();
+
+                        assert_eq!((horizontal, depth), (position.horizontal, position.depth));
+                        assert_eq!(
+                            shortest_course_len(horizontal, depth, forward as i64, aim as i64),
+                            course.len(),
+                            "{} {} {:?}",
+                            horizontal,
+                            depth,
+                            limits
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
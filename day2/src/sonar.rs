@@ -0,0 +1,155 @@
+use std::ops::RangeInclusive;
+
+use day1::DepthProfile;
+
+use crate::{Command, NavigationModel, Position, SurfaceRule};
+
+/// The submarine over one horizontal position of the sonar sweep.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Sounding {
+    /// 1-based index of the command that brought the submarine here, 0 for the start.
+    pub index: usize,
+    pub position: Position,
+    pub sea_floor: usize,
+}
+
+impl Sounding {
+    /// The submarine is below the sea floor.
+    pub fn is_grounded(&self) -> bool {
+        i64::try_from(self.sea_floor).is_ok_and(|floor| self.position.depth > floor)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SoundingReport {
+    /// One sounding for every horizontal position the course passes within the sweep.
+    pub soundings: Vec<Sounding>,
+    /// The course leaves the sweep before it ends, the rest of it is not sounded.
+    pub leaves_sweep: bool,
+}
+
+impl SoundingReport {
+    pub fn groundings(&self) -> impl Iterator<Item = &Sounding> {
+        self.soundings.iter().filter(|s| s.is_grounded())
+    }
+
+    /// Horizontal ranges where the submarine stays below the sea floor.
+    pub fn grounded_ranges(&self) -> Vec<RangeInclusive<i64>> {
+        let mut ranges: Vec<RangeInclusive<i64>> = Vec::new();
+
+        for horizontal in self.groundings().map(|s| s.position.horizontal) {
+            match ranges.last_mut() {
+                Some(range) if *range.end() + 1 == horizontal => {
+                    *range = *range.start()..=horizontal
+                }
+                _ => ranges.push(horizontal..=horizontal),
+            }
+        }
+
+        ranges
+    }
+}
+
+/// Follows the course one horizontal step at a time and looks up the sea floor under every
+/// position, starting with the origin.
+pub fn sound_course(
+    profile: &DepthProfile,
+    model: &dyn NavigationModel,
+    commands: &[Command],
+    surface: SurfaceRule,
+) -> anyhow::Result<SoundingReport> {
+    let sound = |index: usize, position: Position| {
+        usize::try_from(position.horizontal)
+            .ok()
+            .and_then(|horizontal| profile.depth_at(horizontal))
+            .map(|sea_floor| Sounding {
+                index,
+                position,
+                sea_floor,
+            })
+    };
+
+    let mut position = Position::default();
+    let mut soundings = Vec::new();
+
+    match sound(0, position) {
+        Some(sounding) => soundings.push(sounding),
+        None => {
+            return Ok(SoundingReport {
+                soundings,
+                leaves_sweep: !commands.is_empty(),
+            })
+        }
+    }
+
+    for (index, &command) in commands.iter().enumerate() {
+        let index = index + 1;
+
+        if let Command::Forward(value) = command {
+            for _ in 0..value {
+                position = model.step(position, index, Command::Forward(1), surface)?;
+
+                match sound(index, position) {
+                    Some(sounding) => soundings.push(sounding),
+                    None => {
+                        return Ok(SoundingReport {
+                            soundings,
+                            leaves_sweep: true,
+                        })
+                    }
+                }
+            }
+        } else {
+            position = model.step(position, index, command, surface)?;
+        }
+    }
+
+    Ok(SoundingReport {
+        soundings,
+        leaves_sweep: false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sonar::*;
+    use crate::{parse_commands, AimModel, SimpleModel, TEST_DATA};
+
+    #[test]
+    fn test_sound_course() {
+        // A trench at 13 the submarine passes through.
+        let profile = DepthProfile::new(
+            (0..=15)
+                .map(|h| if h == 13 { 100 } else { 10 + 2 * h })
+                .collect(),
+        );
+        let commands = parse_commands(TEST_DATA).unwrap();
+
+        let report = sound_course(&profile, &SimpleModel, &commands, SurfaceRule::Ignore).unwrap();
+        assert_eq!(16, report.soundings.len());
+        assert!(!report.leaves_sweep);
+        assert_eq!(0, report.groundings().count());
+
+        let report = sound_course(&profile, &AimModel, &commands, SurfaceRule::Ignore).unwrap();
+        assert_eq!(
+            Sounding {
+                index: 3,
+                position: Position::new(7, 10, 5),
+                sea_floor: 24
+            },
+            report.soundings[7]
+        );
+        assert_eq!(vec![12..=12, 14..=15], report.grounded_ranges());
+    }
+
+    #[test]
+    fn test_sound_course_leaves_sweep() {
+        let profile = DepthProfile::new(vec![100; 10]);
+        let commands = parse_commands(TEST_DATA).unwrap();
+
+        let report = sound_course(&profile, &AimModel, &commands, SurfaceRule::Ignore).unwrap();
+        assert_eq!(10, report.soundings.len());
+        assert!(report.leaves_sweep);
+        assert_eq!(Position::new(9, 20, 5), report.soundings[9].position);
+    }
+}
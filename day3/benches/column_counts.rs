@@ -0,0 +1,46 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use day3::{count_ones, Reading};
+
+/// Deterministic pseudo-random readings (xorshift64).
+fn report(rows: usize, width: usize) -> Vec<Reading> {
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    let mut next_bit = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state & 1 == 1
+    };
+
+    (0..rows)
+        .map(|_| Reading::from_bits((0..width).map(|_| next_bit())))
+        .collect()
+}
+
+/// The previous approach: one scan of the whole report per column.
+fn count_ones_by_column(data: &[Reading]) -> Vec<usize> {
+    (0..data[0].width())
+        .map(|column| data.iter().filter(|v| v.bit(column)).count())
+        .collect()
+}
+
+fn column_counts(c: &mut Criterion) {
+    let mut group = c.benchmark_group("column counts");
+    group.sample_size(10);
+
+    for width in [12, 128] {
+        let data = report(2_000_000, width);
+
+        group.bench_with_input(BenchmarkId::new("per column", width), &data, |b, data| {
+            b.iter(|| count_ones_by_column(black_box(data)))
+        });
+        group.bench_with_input(BenchmarkId::new("bit-sliced", width), &data, |b, data| {
+            b.iter(|| count_ones(black_box(data)))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, column_counts);
+criterion_main!(benches);
@@ -0,0 +1,194 @@
+use std::io::Write;
+
+use crate::{
+    CO2ScrubberRating, DiagnosticReport, ImportantBit, LifeSupportRating, OxygenGeneratorRating,
+    Reading,
+};
+
+/// The bit counts of a single column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnStats {
+    pub column: usize,
+    pub ones: usize,
+    pub zeros: usize,
+}
+
+impl ColumnStats {
+    /// The most common bit, `None` on a tie.
+    pub fn majority(&self) -> Option<ImportantBit> {
+        match self.ones.cmp(&self.zeros) {
+            std::cmp::Ordering::Greater => Some(ImportantBit::One),
+            std::cmp::Ordering::Less => Some(ImportantBit::Zero),
+            std::cmp::Ordering::Equal => None,
+        }
+    }
+
+    /// How many more readings have the majority bit than the other one.
+    pub fn margin(&self) -> usize {
+        self.ones.abs_diff(self.zeros)
+    }
+}
+
+impl DiagnosticReport {
+    pub fn histogram(&self) -> Vec<ColumnStats> {
+        self.ones()
+            .iter()
+            .enumerate()
+            .map(|(column, &ones)| ColumnStats {
+                column,
+                ones,
+                zeros: self.len() - ones,
+            })
+            .collect()
+    }
+}
+
+/// Per-column counts of a report next to the candidates both life support ratings keep
+/// after filtering on that column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitHistogram {
+    pub columns: Vec<ColumnStats>,
+    pub oxygen_candidates: Vec<usize>,
+    pub co2_candidates: Vec<usize>,
+}
+
+impl BitHistogram {
+    pub fn new(data: &[Reading]) -> anyhow::Result<Self> {
+        Ok(Self {
+            columns: DiagnosticReport::from_readings(data)?.histogram(),
+            oxygen_candidates: OxygenGeneratorRating.candidates(data),
+            co2_candidates: CO2ScrubberRating.candidates(data),
+        })
+    }
+
+    fn rows(&self) -> impl Iterator<Item = [String; 7]> + '_ {
+        self.columns.iter().map(|stats| {
+            let candidates = |steps: &[usize]| {
+                steps
+                    .get(stats.column)
+                    .map(usize::to_string)
+                    .unwrap_or_default()
+            };
+
+            [
+                stats.column.to_string(),
+                stats.ones.to_string(),
+                stats.zeros.to_string(),
+                match stats.majority() {
+                    Some(ImportantBit::One) => "1".to_string(),
+                    Some(ImportantBit::Zero) => "0".to_string(),
+                    None => "tie".to_string(),
+                },
+                stats.margin().to_string(),
+                candidates(&self.oxygen_candidates),
+                candidates(&self.co2_candidates),
+            ]
+        })
+    }
+}
+
+const HEADER: [&str; 7] = [
+    "column", "ones", "zeros", "majority", "margin", "oxygen", "co2",
+];
+
+/// Columns are counted from the most significant bit. Candidate counts are left empty once
+/// a rating has stopped filtering.
+pub fn write_table(histogram: &BitHistogram, out: &mut impl Write) -> std::io::Result<()> {
+    let rows = histogram.rows().collect::<Vec<_>>();
+    let widths = HEADER.map(str::len);
+    let widths = rows.iter().fold(widths, |mut widths, row| {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+        widths
+    });
+
+    let header = HEADER.map(str::to_string);
+    for row in std::iter::once(&header).chain(&rows) {
+        let line = row
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{:>width$}", cell, width = width))
+            .collect::<Vec<_>>()
+            .join("  ");
+        writeln!(out, "{}", line)?;
+    }
+
+    Ok(())
+}
+
+pub fn write_csv(histogram: &BitHistogram, out: &mut impl Write) -> std::io::Result<()> {
+    writeln!(out, "{}", HEADER.join(","))?;
+
+    for row in histogram.rows() {
+        writeln!(out, "{}", row.join(","))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::histogram::*;
+    use crate::{parse_report, TEST_DATA};
+
+    #[test]
+    fn test_histogram() {
+        let data = parse_report(TEST_DATA).unwrap();
+        let histogram = BitHistogram::new(&data).unwrap();
+
+        assert_eq!(
+            ColumnStats {
+                column: 1,
+                ones: 5,
+                zeros: 7
+            },
+            histogram.columns[1]
+        );
+        assert_eq!(Some(ImportantBit::Zero), histogram.columns[1].majority());
+        assert_eq!(2, histogram.columns[1].margin());
+        assert_eq!(vec![7, 4, 3, 2, 1], histogram.oxygen_candidates);
+        assert_eq!(vec![5, 2, 1], histogram.co2_candidates);
+
+        let tie = ColumnStats {
+            column: 0,
+            ones: 3,
+            zeros: 3,
+        };
+        assert_eq!(None, tie.majority());
+        assert_eq!(0, tie.margin());
+    }
+
+    #[test]
+    fn test_write_csv() {
+        let data = parse_report(TEST_DATA).unwrap();
+        let mut out = Vec::new();
+        write_csv(&BitHistogram::new(&data).unwrap(), &mut out).unwrap();
+
+        assert_eq!(
+            "column,ones,zeros,majority,margin,oxygen,co2\n\
+             0,7,5,1,2,7,5\n\
+             1,5,7,0,2,4,2\n\
+             2,8,4,1,4,3,1\n\
+             3,7,5,1,2,2,\n\
+             4,5,7,0,2,1,\n",
+            String::from_utf8(out).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_write_table() {
+        let data = parse_report("10\n11\n").unwrap();
+        let mut out = Vec::new();
+        write_table(&BitHistogram::new(&data).unwrap(), &mut out).unwrap();
+
+        assert_eq!(
+            concat!(
+                "column  ones  zeros  majority  margin  oxygen  co2\n",
+                "     0     2      0         1       2       2    0\n",
+                "     1     1      1       tie       0       1     \n",
+            ),
+            String::from_utf8(out).unwrap()
+        );
+    }
+}
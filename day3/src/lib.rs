@@ -0,0 +1,837 @@
+mod histogram;
+
+pub use histogram::*;
+
+use std::str::FromStr;
+
+use anyhow::{bail, Context};
+use num_bigint::BigUint;
+
+pub const INPUT: &str = include_str!("input.txt");
+
+/// A diagnostic reading of any width.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Reading {
+    /// Bit columns packed from the left, the first column is the most significant bit of
+    /// the first word. Unused bits of the last word are zero, so readings of the same width
+    /// compare like the numbers they represent.
+    words: Vec<u64>,
+    width: usize,
+}
+
+impl Reading {
+    pub fn from_bits(bits: impl IntoIterator<Item = bool>) -> Self {
+        let mut words = Vec::new();
+        let mut width = 0;
+
+        for bit in bits {
+            if width % 64 == 0 {
+                words.push(0);
+            }
+            if bit {
+                *words.last_mut().unwrap() |= 1 << (63 - width % 64);
+            }
+            width += 1;
+        }
+
+        Self { words, width }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The bit in `column`, counted from the left.
+    pub fn bit(&self, column: usize) -> bool {
+        assert!(column < self.width);
+        self.words[column / 64] & (1 << (63 - column % 64)) != 0
+    }
+
+    pub fn value(&self) -> BigUint {
+        let bytes = self
+            .words
+            .iter()
+            .flat_map(|word| word.to_be_bytes())
+            .collect::<Vec<_>>();
+
+        BigUint::from_bytes_be(&bytes) >> (self.words.len() * 64 - self.width)
+    }
+
+    /// The same bits in the opposite order.
+    pub fn reversed(&self) -> Self {
+        Reading::from_bits((0..self.width).rev().map(|column| self.bit(column)))
+    }
+}
+
+impl FromStr for Reading {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.chars()
+            .map(|c| match c {
+                '0' => Ok(false),
+                '1' => Ok(true),
+                _ => bail!("Invalid bit {:?}", c),
+            })
+            .collect::<anyhow::Result<Vec<_>>>()
+            .map(Reading::from_bits)
+    }
+}
+
+/// Parses one reading per line, empty lines are skipped. All readings must be equally wide.
+pub fn parse_report(input: &str) -> anyhow::Result<Vec<Reading>> {
+    let mut width = None;
+
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(index, line)| {
+            let reading = line
+                .parse::<Reading>()
+                .with_context(|| format!("Line {}: {:?}", index + 1, line))?;
+
+            match width.get_or_insert(reading.width()) {
+                width if *width == reading.width() => Ok(reading),
+                width => bail!(
+                    "Line {}: expected {} bits, got {}",
+                    index + 1,
+                    width,
+                    reading.width()
+                ),
+            }
+        })
+        .collect()
+}
+
+/// How `power_consumption` treats a column with as many ones as zeros.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TieBreak {
+    Prefer(ImportantBit),
+    Error,
+}
+
+pub fn power_consumption(data: &[Reading], tie_break: TieBreak) -> anyhow::Result<BigUint> {
+    DiagnosticReport::from_readings(data)?.power_consumption(tie_break)
+}
+
+/// Per-column counts of ones in a changing set of equally wide readings.
+///
+/// Readings can be added and removed one at a time, and the rates are derived from the
+/// counts alone, in time proportional to the width.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DiagnosticReport {
+    width: Option<usize>,
+    len: usize,
+    ones: Vec<usize>,
+}
+
+impl DiagnosticReport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_readings(data: &[Reading]) -> anyhow::Result<Self> {
+        let width = data.first().map(Reading::width);
+
+        if let Some(reading) = data.iter().find(|v| Some(v.width()) != width) {
+            bail!(
+                "Expected {} bits, got {}",
+                width.unwrap_or_default(),
+                reading.width()
+            );
+        }
+
+        Ok(Self {
+            width,
+            len: data.len(),
+            ones: count_ones(data),
+        })
+    }
+
+    /// The width of the readings, fixed by the first one added.
+    pub fn width(&self) -> Option<usize> {
+        self.width
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The number of ones in every column.
+    pub fn ones(&self) -> &[usize] {
+        &self.ones
+    }
+
+    pub fn add(&mut self, reading: &Reading) -> anyhow::Result<()> {
+        let width = *self.width.get_or_insert(reading.width());
+        if width != reading.width() {
+            bail!("Expected {} bits, got {}", width, reading.width());
+        }
+
+        self.ones.resize(width, 0);
+        for (column, count) in self.ones.iter_mut().enumerate() {
+            *count += reading.bit(column) as usize;
+        }
+        self.len += 1;
+
+        Ok(())
+    }
+
+    /// Takes a previously added reading out of the counts. The report is left unchanged when
+    /// the reading cannot be one of its readings.
+    pub fn remove(&mut self, reading: &Reading) -> anyhow::Result<()> {
+        if self.width != Some(reading.width()) {
+            bail!("Reading {:?} is not in the report", reading);
+        }
+        if let Some(column) =
+            (0..reading.width()).find(|&column| reading.bit(column) && self.ones[column] == 0)
+        {
+            bail!("Column {} has no ones to remove", column);
+        }
+        if let Some(column) = (0..reading.width())
+            .find(|&column| !reading.bit(column) && self.ones[column] == self.len)
+        {
+            bail!("Column {} has no zeros to remove", column);
+        }
+
+        for (column, count) in self.ones.iter_mut().enumerate() {
+            *count -= reading.bit(column) as usize;
+        }
+        self.len -= 1;
+
+        Ok(())
+    }
+
+    /// The most common bit of every column.
+    pub fn gamma(&self, tie_break: TieBreak) -> anyhow::Result<BigUint> {
+        self.gamma_reading(tie_break).map(|gamma| gamma.value())
+    }
+
+    /// The least common bit of every column, ties are resolved as for `gamma`.
+    pub fn epsilon(&self, tie_break: TieBreak) -> anyhow::Result<BigUint> {
+        self.gamma_reading(tie_break)
+            .map(|gamma| Self::complement(&gamma).value())
+    }
+
+    pub fn power_consumption(&self, tie_break: TieBreak) -> anyhow::Result<BigUint> {
+        let gamma = self.gamma_reading(tie_break)?;
+        Ok(gamma.value() * Self::complement(&gamma).value())
+    }
+
+    fn gamma_reading(&self, tie_break: TieBreak) -> anyhow::Result<Reading> {
+        if self.is_empty() {
+            bail!("Empty report");
+        }
+
+        self.ones
+            .iter()
+            .enumerate()
+            .map(|(column, &ones)| {
+                let zeros = self.len - ones;
+
+                match ones.cmp(&zeros) {
+                    std::cmp::Ordering::Greater => Ok(true),
+                    std::cmp::Ordering::Less => Ok(false),
+                    std::cmp::Ordering::Equal => match tie_break {
+                        TieBreak::Prefer(ImportantBit::One) => Ok(true),
+                        TieBreak::Prefer(ImportantBit::Zero) => Ok(false),
+                        TieBreak::Error => bail!("Column {} has {} ones and zeros", column, ones),
+                    },
+                }
+            })
+            .collect::<anyhow::Result<Vec<_>>>()
+            .map(Reading::from_bits)
+    }
+
+    fn complement(reading: &Reading) -> Reading {
+        Reading::from_bits((0..reading.width()).map(|column| !reading.bit(column)))
+    }
+}
+
+impl FromStr for DiagnosticReport {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_readings(&parse_report(s)?)
+    }
+}
+
+/// Counts the ones in every column of equally wide readings in a single pass.
+///
+/// Bit `j` of `slices[w][k]` holds bit `k` of a partial count for column `64 * w + j`, so
+/// a whole word of columns is added with a fixed run of bitwise operations. The partial
+/// counts are moved into `counts` before they can overflow.
+pub fn count_ones(data: &[Reading]) -> Vec<usize> {
+    const SLICES: usize = 8;
+    const ROWS_PER_FLUSH: usize = (1 << SLICES) - 1;
+
+    let first = match data.first() {
+        Some(first) => first,
+        None => return Vec::new(),
+    };
+
+    let mut counts = vec![0_usize; first.width()];
+    let mut slices = vec![[0_u64; SLICES]; first.words.len()];
+
+    for chunk in data.chunks(ROWS_PER_FLUSH) {
+        for reading in chunk {
+            for (slice, &word) in slices.iter_mut().zip(&reading.words) {
+                let mut carry = word;
+                for bit in slice.iter_mut() {
+                    let next = *bit & carry;
+                    *bit ^= carry;
+                    carry = next;
+                }
+            }
+        }
+
+        for (column, count) in counts.iter_mut().enumerate() {
+            let slice = &slices[column / 64];
+            let shift = 63 - column % 64;
+            *count += slice
+                .iter()
+                .enumerate()
+                .map(|(k, bits)| (((bits >> shift) & 1) as usize) << k)
+                .sum::<usize>();
+        }
+
+        slices.iter_mut().for_each(|slice| *slice = [0; SLICES]);
+    }
+
+    counts
+}
+
+pub fn life_support_rating(data: &[Reading]) -> Option<BigUint> {
+    let sorted = sorted(data);
+
+    narrow(&OxygenGeneratorRating, &sorted)
+        .zip(narrow(&CO2ScrubberRating, &sorted))
+        .map(|(oxy, co2)| oxy.value() * co2.value())
+}
+
+fn sorted(data: &[Reading]) -> Vec<&Reading> {
+    let mut sorted = data.iter().collect::<Vec<_>>();
+    sorted.sort_unstable();
+    sorted
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Selector {
+    Fewer,
+    More,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ImportantBit {
+    Zero,
+    One,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ScanDirection {
+    MostSignificantFirst,
+    LeastSignificantFirst,
+}
+
+/// The share of ones in a column above which one is the most common bit.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Threshold {
+    numerator: usize,
+    denominator: usize,
+}
+
+impl Threshold {
+    pub const HALF: Threshold = Threshold {
+        numerator: 1,
+        denominator: 2,
+    };
+
+    /// `None` unless `0 <= numerator / denominator <= 1`.
+    pub fn new(numerator: usize, denominator: usize) -> Option<Self> {
+        (denominator > 0 && numerator <= denominator).then_some(Self {
+            numerator,
+            denominator,
+        })
+    }
+}
+
+/// A bit criterion that filters the readings column by column until one is left.
+/// Readings of different widths have no rating.
+///
+/// Ones are the most common bit of a column when their share is above the threshold,
+/// and ties go to the important bit.
+pub trait LifeSupportRating {
+    fn selector(&self) -> Selector;
+    fn important_bit(&self) -> ImportantBit;
+
+    fn threshold(&self) -> Threshold {
+        Threshold::HALF
+    }
+
+    fn direction(&self) -> ScanDirection {
+        ScanDirection::MostSignificantFirst
+    }
+
+    fn calculate(&self, data: &[Reading]) -> Option<BigUint> {
+        match self.direction() {
+            ScanDirection::MostSignificantFirst => narrow(self, &sorted(data)).map(Reading::value),
+            ScanDirection::LeastSignificantFirst => {
+                let reversed = data.iter().map(Reading::reversed).collect::<Vec<_>>();
+                narrow(self, &sorted(&reversed)).map(|reading| reading.reversed().value())
+            }
+        }
+    }
+
+    /// The number of readings left after each filtering step, in scan order. Empty when the
+    /// readings differ in width.
+    fn candidates(&self, data: &[Reading]) -> Vec<usize> {
+        let mut remaining = Vec::new();
+        let trace = |count| remaining.push(count);
+
+        match self.direction() {
+            ScanDirection::MostSignificantFirst => {
+                narrow_traced(self, &sorted(data), trace);
+            }
+            ScanDirection::LeastSignificantFirst => {
+                let reversed = data.iter().map(Reading::reversed).collect::<Vec<_>>();
+                narrow_traced(self, &sorted(&reversed), trace);
+            }
+        }
+
+        remaining
+    }
+}
+
+/// Filters sorted readings column by column, ignoring the scan direction. Readings left after
+/// a column share all bits before it, so they stay a contiguous range with zeros in that
+/// column first.
+fn narrow<'a, R>(rating: &R, sorted: &[&'a Reading]) -> Option<&'a Reading>
+where
+    R: LifeSupportRating + ?Sized,
+{
+    narrow_traced(rating, sorted, |_| ())
+}
+
+/// `narrow` that passes the number of readings left after each column to `trace`.
+fn narrow_traced<'a, R>(
+    rating: &R,
+    sorted: &[&'a Reading],
+    mut trace: impl FnMut(usize),
+) -> Option<&'a Reading>
+where
+    R: LifeSupportRating + ?Sized,
+{
+    if sorted
+        .windows(2)
+        .any(|pair| pair[0].width() != pair[1].width())
+    {
+        return None;
+    }
+
+    let threshold = rating.threshold();
+    let mut data = sorted;
+    let mut column = 0;
+
+    while data.len() > 1 && column < data[0].width() {
+        let (zeros, ones) = data.split_at(data.partition_point(|v| !v.bit(column)));
+        let share = ones.len() * threshold.denominator;
+        let limit = data.len() * threshold.numerator;
+
+        data = match share.cmp(&limit) {
+            std::cmp::Ordering::Greater => match rating.selector() {
+                Selector::Fewer => zeros,
+                Selector::More => ones,
+            },
+            std::cmp::Ordering::Less => match rating.selector() {
+                Selector::Fewer => ones,
+                Selector::More => zeros,
+            },
+            std::cmp::Ordering::Equal => match rating.important_bit() {
+                ImportantBit::Zero => zeros,
+                ImportantBit::One => ones,
+            },
+        };
+
+        trace(data.len());
+        column += 1;
+    }
+
+    match data {
+        [reading] => Some(reading),
+        _ => None,
+    }
+}
+
+pub struct OxygenGeneratorRating;
+pub struct CO2ScrubberRating;
+
+/// The oxygen generator criterion scanned from the least significant bit.
+pub struct ReverseOxygenGeneratorRating;
+
+/// The CO2 scrubber criterion scanned from the least significant bit.
+pub struct ReverseCO2ScrubberRating;
+
+/// Keeps the ones only where at least two thirds of the readings have them.
+pub struct SupermajorityRating;
+
+/// A rating assembled from its parts.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct CustomRating {
+    pub selector: Selector,
+    pub important_bit: ImportantBit,
+    pub threshold: Threshold,
+    pub direction: ScanDirection,
+}
+
+impl LifeSupportRating for OxygenGeneratorRating {
+    fn selector(&self) -> Selector {
+        Selector::More
+    }
+
+    fn important_bit(&self) -> ImportantBit {
+        ImportantBit::One
+    }
+}
+
+impl LifeSupportRating for CO2ScrubberRating {
+    fn selector(&self) -> Selector {
+        Selector::Fewer
+    }
+
+    fn important_bit(&self) -> ImportantBit {
+        ImportantBit::Zero
+    }
+}
+
+impl LifeSupportRating for ReverseOxygenGeneratorRating {
+    fn selector(&self) -> Selector {
+        Selector::More
+    }
+
+    fn important_bit(&self) -> ImportantBit {
+        ImportantBit::One
+    }
+
+    fn direction(&self) -> ScanDirection {
+        ScanDirection::LeastSignificantFirst
+    }
+}
+
+impl LifeSupportRating for ReverseCO2ScrubberRating {
+    fn selector(&self) -> Selector {
+        Selector::Fewer
+    }
+
+    fn important_bit(&self) -> ImportantBit {
+        ImportantBit::Zero
+    }
+
+    fn direction(&self) -> ScanDirection {
+        ScanDirection::LeastSignificantFirst
+    }
+}
+
+impl LifeSupportRating for SupermajorityRating {
+    fn selector(&self) -> Selector {
+        Selector::More
+    }
+
+    fn important_bit(&self) -> ImportantBit {
+        ImportantBit::One
+    }
+
+    fn threshold(&self) -> Threshold {
+        Threshold {
+            numerator: 2,
+            denominator: 3,
+        }
+    }
+}
+
+impl LifeSupportRating for CustomRating {
+    fn selector(&self) -> Selector {
+        self.selector
+    }
+
+    fn important_bit(&self) -> ImportantBit {
+        self.important_bit
+    }
+
+    fn threshold(&self) -> Threshold {
+        self.threshold
+    }
+
+    fn direction(&self) -> ScanDirection {
+        self.direction
+    }
+}
+
+#[allow(dead_code)]
+const TEST_DATA: &str =
+    "00100\n11110\n10110\n10111\n10101\n01111\n00111\n11100\n10000\n11001\n00010\n01010\n";
+
+#[test]
+fn test_reading() {
+    let reading = "10110".parse::<Reading>().unwrap();
+    assert_eq!(5, reading.width());
+    assert!(reading.bit(0));
+    assert!(!reading.bit(1));
+    assert_eq!(BigUint::from(0b10110_u32), reading.value());
+
+    assert!("10210".parse::<Reading>().is_err());
+    assert!(parse_report("101\n1010\n").is_err());
+}
+
+#[test]
+fn test_wide_reading() {
+    let wide = format!("1{}1", "0".repeat(128));
+    let reading = wide.parse::<Reading>().unwrap();
+    assert_eq!(130, reading.width());
+    assert_eq!((BigUint::from(1_u32) << 129) + 1_u32, reading.value());
+
+    let other = format!("1{}", "0".repeat(129));
+    let report = parse_report(&format!("{}\n{}\n{}\n", wide, other, "0".repeat(130))).unwrap();
+    let gamma = other.parse::<Reading>().unwrap().value();
+    let epsilon = (BigUint::from(1_u32) << 130) - 1_u32 - &gamma;
+    assert_eq!(
+        gamma * epsilon,
+        power_consumption(&report, TieBreak::Error).unwrap()
+    );
+    assert_eq!(
+        Some(reading.value()),
+        OxygenGeneratorRating.calculate(&report)
+    );
+    assert_eq!(
+        Some(BigUint::from(0_u32)),
+        CO2ScrubberRating.calculate(&report)
+    );
+}
+
+#[test]
+fn test_calculate_rating() {
+    let data = parse_report(TEST_DATA).unwrap();
+    assert_eq!(
+        Some(BigUint::from(23_u32)),
+        OxygenGeneratorRating.calculate(&data)
+    );
+    assert_eq!(
+        Some(BigUint::from(10_u32)),
+        CO2ScrubberRating.calculate(&data)
+    );
+}
+
+#[test]
+fn test_calculate_rating_edge_cases() {
+    assert_eq!(None, OxygenGeneratorRating.calculate(&[]));
+
+    // Identical readings never narrow down to one.
+    let data = parse_report("101\n101\n").unwrap();
+    assert_eq!(None, OxygenGeneratorRating.calculate(&data));
+
+    // The fewer side of a column without zeros is empty.
+    let data = parse_report("110\n111\n").unwrap();
+    assert_eq!(None, CO2ScrubberRating.calculate(&data));
+    assert_eq!(
+        Some(BigUint::from(0b111_u32)),
+        OxygenGeneratorRating.calculate(&data)
+    );
+}
+
+#[test]
+fn test_candidates() {
+    let data = parse_report(TEST_DATA).unwrap();
+    assert_eq!(vec![7, 4, 3, 2, 1], OxygenGeneratorRating.candidates(&data));
+    assert_eq!(vec![5, 2, 1], CO2ScrubberRating.candidates(&data));
+    assert!(OxygenGeneratorRating.candidates(&[]).is_empty());
+}
+
+#[test]
+fn test_mixed_widths() {
+    let data = ["1010", "101", "0110"].map(|v| v.parse::<Reading>().unwrap());
+
+    assert_eq!(None, OxygenGeneratorRating.calculate(&data));
+    assert_eq!(None, ReverseCO2ScrubberRating.calculate(&data));
+    assert!(CO2ScrubberRating.candidates(&data).is_empty());
+    assert!(ReverseOxygenGeneratorRating.candidates(&data).is_empty());
+    assert_eq!(None, life_support_rating(&data));
+}
+
+#[test]
+fn test_count_ones() {
+    let data = parse_report(TEST_DATA).unwrap();
+    assert_eq!(vec![7, 5, 8, 7, 5], count_ones(&data));
+
+    // Wider than a word and longer than a flush.
+    let data = (0..1000)
+        .map(|row| Reading::from_bits((0..130).map(|column| (row + column) % 3 == 0)))
+        .collect::<Vec<_>>();
+    let expected = (0..130)
+        .map(|column| data.iter().filter(|v| v.bit(column)).count())
+        .collect::<Vec<_>>();
+    assert_eq!(expected, count_ones(&data));
+
+    assert!(count_ones(&[]).is_empty());
+}
+
+#[test]
+fn test_extra_ratings() {
+    let data = parse_report(TEST_DATA).unwrap();
+
+    assert_eq!(
+        Some(BigUint::from(0b11110_u32)),
+        ReverseOxygenGeneratorRating.calculate(&data)
+    );
+    assert_eq!(
+        Some(BigUint::from(0b11001_u32)),
+        ReverseCO2ScrubberRating.calculate(&data)
+    );
+    assert_eq!(
+        Some(BigUint::from(0b00100_u32)),
+        SupermajorityRating.calculate(&data)
+    );
+
+    let rating = CustomRating {
+        selector: Selector::Fewer,
+        important_bit: ImportantBit::Zero,
+        threshold: Threshold::new(2, 3).unwrap(),
+        direction: ScanDirection::MostSignificantFirst,
+    };
+    assert_eq!(Some(BigUint::from(0b11001_u32)), rating.calculate(&data));
+
+    let rating = CustomRating {
+        direction: ScanDirection::LeastSignificantFirst,
+        threshold: Threshold::HALF,
+        ..rating
+    };
+    assert_eq!(
+        ReverseCO2ScrubberRating.calculate(&data),
+        rating.calculate(&data)
+    );
+}
+
+#[test]
+fn test_threshold() {
+    assert_eq!(Some(Threshold::HALF), Threshold::new(1, 2));
+    assert!(Threshold::new(0, 1).is_some());
+    assert!(Threshold::new(1, 0).is_none());
+    assert!(Threshold::new(3, 2).is_none());
+}
+
+#[test]
+fn test_diagnostic_report() {
+    let data = parse_report(TEST_DATA).unwrap();
+    let mut report = DiagnosticReport::new();
+    assert!(report.power_consumption(TieBreak::Error).is_err());
+
+    for reading in &data {
+        report.add(reading).unwrap();
+    }
+    assert_eq!(TEST_DATA.parse::<DiagnosticReport>().unwrap(), report);
+    assert_eq!(vec![7, 5, 8, 7, 5], report.ones());
+    assert_eq!(
+        BigUint::from(22_u32),
+        report.gamma(TieBreak::Error).unwrap()
+    );
+    assert_eq!(
+        BigUint::from(9_u32),
+        report.epsilon(TieBreak::Error).unwrap()
+    );
+    assert_eq!(
+        BigUint::from(198_u32),
+        report.power_consumption(TieBreak::Error).unwrap()
+    );
+
+    // Dropping 10110 and 10111 ties the first, second and fourth columns.
+    report.remove(&data[2]).unwrap();
+    report.remove(&data[3]).unwrap();
+    assert_eq!(10, report.len());
+    assert!(report.gamma(TieBreak::Error).is_err());
+    assert_eq!(
+        BigUint::from(0b11110_u32),
+        report.gamma(TieBreak::Prefer(ImportantBit::One)).unwrap()
+    );
+    assert_eq!(
+        BigUint::from(0b11011_u32),
+        report
+            .epsilon(TieBreak::Prefer(ImportantBit::Zero))
+            .unwrap()
+    );
+    assert_eq!(
+        power_consumption(
+            &[&data[..2], &data[4..]].concat(),
+            TieBreak::Prefer(ImportantBit::One)
+        )
+        .unwrap(),
+        report
+            .power_consumption(TieBreak::Prefer(ImportantBit::One))
+            .unwrap()
+    );
+
+    assert!(report.add(&"101".parse().unwrap()).is_err());
+    assert!(report.remove(&"101".parse().unwrap()).is_err());
+}
+
+#[test]
+fn test_diagnostic_report_remove() {
+    let mut report = "110\n100\n".parse::<DiagnosticReport>().unwrap();
+    let before = report.clone();
+
+    // Neither reading has a one in the last column or a zero in the first.
+    assert!(report.remove(&"001".parse().unwrap()).is_err());
+    assert!(report.remove(&"010".parse().unwrap()).is_err());
+    assert_eq!(before, report);
+
+    report.remove(&"110".parse().unwrap()).unwrap();
+    report.remove(&"100".parse().unwrap()).unwrap();
+    assert!(report.is_empty());
+    assert!(report.remove(&"100".parse().unwrap()).is_err());
+}
+
+#[test]
+fn test_power_consumption() {
+    let data = parse_report(TEST_DATA).unwrap();
+    assert_eq!(
+        BigUint::from(198_u32),
+        power_consumption(&data, TieBreak::Error).unwrap()
+    );
+}
+
+#[test]
+fn test_power_consumption_odd_report() {
+    // One of three is a minority, not the most common bit: gamma 010 and epsilon 101.
+    let data = parse_report("110\n011\n000\n").unwrap();
+    assert_eq!(
+        BigUint::from(0b010_u32 * 0b101),
+        power_consumption(&data, TieBreak::Error).unwrap()
+    );
+}
+
+#[test]
+fn test_power_consumption_tie_break() {
+    // The first column is tied, the rest give gamma x10 and epsilon x01.
+    let data = parse_report("110\n111\n010\n000\n").unwrap();
+    assert_eq!(
+        BigUint::from(0b110_u32),
+        power_consumption(&data, TieBreak::Prefer(ImportantBit::One)).unwrap()
+    );
+    assert_eq!(
+        BigUint::from(0b010_u32 * 0b101),
+        power_consumption(&data, TieBreak::Prefer(ImportantBit::Zero)).unwrap()
+    );
+
+    let error = power_consumption(&data, TieBreak::Error).unwrap_err();
+    assert_eq!("Column 0 has 2 ones and zeros", error.to_string());
+
+    assert!(power_consumption(&[], TieBreak::Error).is_err());
+}
+
+#[test]
+fn test_life_support_rating() {
+    let data = parse_report(TEST_DATA).unwrap();
+    assert_eq!(Some(BigUint::from(230_u32)), life_support_rating(&data));
+}
@@ -0,0 +1,72 @@
+use std::net::TcpListener;
+use std::str::FromStr;
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Context};
+
+use day4::*;
+
+/// Usage: `server [--port PORT] [--players N] [--interval MS] [--write-timeout MS] [--size WxH]
+/// [--max N] [--seed N] [rule...]`.
+/// Waits for the players on localhost, then draws a number every interval until every player
+/// has won. Clients speak the line protocol described in `day4::server`, e.g. through `nc`.
+fn main() -> anyhow::Result<()> {
+    let mut args = std::env::args().skip(1);
+    let mut config = ServerConfig::default();
+    let mut port = 7878;
+    let mut rules = Vec::new();
+
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| anyhow!("Missing value for {}", arg))
+        };
+
+        match arg.as_str() {
+            "--port" => port = parse(&arg, value()?)?,
+            "--players" => config.players = parse(&arg, value()?)?,
+            "--interval" => config.interval = Duration::from_millis(parse(&arg, value()?)?),
+            "--write-timeout" => {
+                config.write_timeout = Duration::from_millis(parse(&arg, value()?)?)
+            }
+            "--max" => config.max_number = parse(&arg, value()?)?,
+            "--seed" => config.seed = parse(&arg, value()?)?,
+            "--size" => {
+                let value = value()?;
+                let (width, height) = value
+                    .split_once('x')
+                    .ok_or_else(|| anyhow!("Invalid size {:?}", value))?;
+                config.width = parse(&arg, width.to_string())?;
+                config.height = parse(&arg, height.to_string())?;
+            }
+            option if option.starts_with("--") => bail!("Unknown option {}", option),
+            rule => rules.push(rule.parse::<WinRule>()?),
+        }
+    }
+
+    if !rules.is_empty() {
+        config.rules = rules;
+    }
+
+    let listener = TcpListener::bind(("127.0.0.1", port))?;
+    println!("Listening on {}", listener.local_addr()?);
+
+    for claim in serve(listener, config)? {
+        println!(
+            "Player {} won on draw {} with {} ({})",
+            claim.player, claim.turn, claim.score, claim.pattern
+        );
+    }
+
+    Ok(())
+}
+
+fn parse<T>(arg: &str, value: String) -> anyhow::Result<T>
+where
+    T: FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    value
+        .parse()
+        .with_context(|| format!("Invalid value {:?} for {}", value, arg))
+}
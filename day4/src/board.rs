@@ -0,0 +1,398 @@
+use std::iter::Enumerate;
+use std::num::IntErrorKind;
+use std::str::Lines;
+
+use anyhow::{anyhow, Context};
+
+use crate::{Pattern, Win, WinRule};
+
+/// Comma separated numbers.
+pub fn parse_draw_numbers(input: &str) -> anyhow::Result<Vec<u32>> {
+    input
+        .split(',')
+        .enumerate()
+        .map(|(index, s)| parse_number(s.trim()).with_context(|| format!("Draw {}", index + 1)))
+        .collect()
+}
+
+fn parse_number(s: &str) -> anyhow::Result<u32> {
+    s.parse::<u32>().map_err(|e| match e.kind() {
+        IntErrorKind::Empty => anyhow!("Missing number"),
+        IntErrorKind::PosOverflow => anyhow!("{:?} is above {}", s, u32::MAX),
+        _ => anyhow!("{:?} is not a number", s),
+    })
+}
+
+pub struct BoardParser<'a> {
+    lines: Enumerate<Lines<'a>>,
+    first_line: usize,
+    rules: &'a [WinRule],
+}
+
+impl<'a> BoardParser<'a> {
+    /// `first_line` is the line number of the first of `lines` in error messages.
+    pub fn new(lines: Lines<'a>, first_line: usize, rules: &'a [WinRule]) -> Self {
+        Self {
+            lines: lines.enumerate(),
+            first_line,
+            rules,
+        }
+    }
+}
+
+impl<'a> Iterator for BoardParser<'a> {
+    type Item = anyhow::Result<Board>;
+
+    /// A board is a block of rows up to the next empty line, its size is taken from the block.
+    fn next(&mut self) -> Option<Self::Item> {
+        let first_line = self.first_line;
+        let rows = self
+            .lines
+            .by_ref()
+            .skip_while(|(_, line)| line.trim().is_empty())
+            .take_while(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                line.split_ascii_whitespace()
+                    .map(parse_number)
+                    .collect::<anyhow::Result<Vec<_>>>()
+                    .with_context(|| format!("Line {}: {:?}", first_line + index, line))
+                    .map(|row| (first_line + index, row))
+            })
+            .collect::<anyhow::Result<Vec<_>>>();
+
+        let rows = match rows {
+            Ok(rows) if rows.is_empty() => return None,
+            Ok(rows) => rows,
+            Err(e) => return Some(Err(e)),
+        };
+
+        let width = rows[0].1.len();
+        if let Some((line, row)) = rows.iter().find(|(_, row)| row.len() != width) {
+            return Some(Err(anyhow!(
+                "Line {}: expected {} numbers, got {}",
+                line,
+                width,
+                row.len()
+            )));
+        }
+
+        let height = rows.len();
+        let values = rows.into_iter().flat_map(|(_, row)| row).collect();
+        Some(Ok(Board::new(width, height, values, self.rules)))
+    }
+}
+
+/// A fixed-size set of cell positions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bitset {
+    words: Vec<u64>,
+}
+
+impl Bitset {
+    pub fn new(len: usize) -> Self {
+        Self {
+            words: vec![0; len.div_ceil(64)],
+        }
+    }
+
+    pub fn from_positions(len: usize, positions: impl IntoIterator<Item = usize>) -> Self {
+        let mut bitset = Self::new(len);
+        for pos in positions {
+            bitset.insert(pos);
+        }
+        bitset
+    }
+
+    pub fn insert(&mut self, pos: usize) {
+        self.words[pos / 64] |= 1 << (pos % 64);
+    }
+
+    pub fn contains(&self, pos: usize) -> bool {
+        self.words[pos / 64] & (1 << (pos % 64)) != 0
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(index, &word)| {
+            (0..64)
+                .filter(move |bit| word & (1 << bit) != 0)
+                .map(move |bit| index * 64 + bit)
+        })
+    }
+
+    pub fn union(&self, other: &Bitset) -> Bitset {
+        Self {
+            words: self
+                .words
+                .iter()
+                .zip(&other.words)
+                .map(|(a, b)| a | b)
+                .collect(),
+        }
+    }
+
+    pub fn is_superset(&self, other: &Bitset) -> bool {
+        self.words
+            .iter()
+            .zip(&other.words)
+            .all(|(&word, &mask)| word & mask == mask)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Board {
+    width: usize,
+    height: usize,
+    /// Row by row.
+    values: Vec<u32>,
+    flags: Bitset,
+    /// The patterns of every rule of the game, in rule order.
+    patterns: Vec<(Pattern, Bitset)>,
+    win: Option<Win>,
+}
+
+impl Board {
+    pub fn new(width: usize, height: usize, values: Vec<u32>, rules: &[WinRule]) -> Self {
+        assert_eq!(width * height, values.len());
+
+        Self {
+            width,
+            height,
+            values,
+            flags: Bitset::new(width * height),
+            patterns: rules
+                .iter()
+                .flat_map(|rule| rule.patterns(width, height))
+                .collect(),
+            win: None,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Row by row.
+    pub fn values(&self) -> &[u32] {
+        &self.values
+    }
+
+    pub fn is_marked(&self, pos: usize) -> bool {
+        self.flags.contains(pos)
+    }
+
+    /// Cell masks of every rule of the game, in rule order.
+    pub fn patterns(&self) -> &[(Pattern, Bitset)] {
+        &self.patterns
+    }
+
+    pub fn score(&self) -> Option<u128> {
+        self.win.as_ref().map(|win| win.score)
+    }
+
+    pub fn win(&self) -> Option<&Win> {
+        self.win.as_ref()
+    }
+
+    pub fn draw(&mut self, num: u32) -> Option<Win> {
+        if self.win.is_none() {
+            self.win = self.values.iter().position(|&x| x == num).and_then(|pos| {
+                self.set_flag(pos);
+
+                self.marked_pattern().map(|pattern| Win {
+                    score: self.calculate_result(num),
+                    pattern: pattern.clone(),
+                })
+            });
+        }
+
+        self.win.clone()
+    }
+
+    fn set_flag(&mut self, pos: usize) {
+        assert!(pos < self.values.len());
+        self.flags.insert(pos);
+    }
+
+    /// The first pattern whose cells are all marked.
+    fn marked_pattern(&self) -> Option<&Pattern> {
+        self.patterns
+            .iter()
+            .find(|(_, mask)| self.flags.is_superset(mask))
+            .map(|(pattern, _)| pattern)
+    }
+
+    /// Wide enough for any number of cells with any values.
+    fn calculate_result(&self, num: u32) -> u128 {
+        num as u128
+            * self
+                .values
+                .iter()
+                .enumerate()
+                .filter(|&(pos, _)| !self.flags.contains(pos))
+                .map(|(_, &v)| v as u128)
+                .sum::<u128>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Range;
+
+    use crate::board::*;
+
+    #[test]
+    fn test_board_rows() {
+        let drawer = |r: Range<u32>| {
+            let mut board = Board::new(5, 5, (0..25).collect(), &WinRule::STANDARD);
+
+            r.map(|x| board.draw(x))
+                .last()
+                .flatten()
+                .map(|win| win.score)
+        };
+
+        let summator = |r: Range<u32>| {
+            Some((r.end - 1) as u128 * ((0..25).sum::<u128>() - r.map(|x| x as u128).sum::<u128>()))
+        };
+
+        assert_eq!(summator(0..5), drawer(0..5));
+        assert_eq!(summator(5..10), drawer(5..10));
+        assert_eq!(summator(10..15), drawer(10..15));
+        assert_eq!(summator(15..20), drawer(15..20));
+        assert_eq!(summator(20..25), drawer(20..25));
+    }
+
+    #[test]
+    fn test_board_columns() {
+        let drawer = |r: [u32; 5]| {
+            let mut board = Board::new(5, 5, (0..25).collect(), &WinRule::STANDARD);
+
+            r.iter()
+                .map(|&x| board.draw(x))
+                .last()
+                .flatten()
+                .map(|win| win.score)
+        };
+
+        let summator = |r: [u32; 5]| {
+            Some(
+                (r[4]) as u128
+                    * ((0..25).sum::<u128>() - r.iter().map(|&x| x as u128).sum::<u128>()),
+            )
+        };
+
+        let verify = |r: [u32; 5]| assert_eq!(summator(r), drawer(r));
+
+        verify([0, 5, 10, 15, 20]);
+        verify([1, 6, 11, 16, 21]);
+        verify([2, 7, 12, 17, 22]);
+        verify([3, 8, 13, 18, 23]);
+        verify([4, 9, 14, 19, 24]);
+    }
+
+    #[test]
+    fn test_board_sizes() {
+        let input = "1 2 3\n4 5 6\n7 8 9\n\n1 2 3 4\n5 6 7 8\n9 10 11 12\n";
+        let mut boards = BoardParser::new(input.lines(), 1, &WinRule::STANDARD)
+            .collect::<anyhow::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(
+            vec![(3, 3), (4, 3)],
+            boards
+                .iter()
+                .map(|b| (b.width, b.height))
+                .collect::<Vec<_>>()
+        );
+
+        assert_eq!(None, boards[0].draw(2));
+        assert_eq!(None, boards[0].draw(5));
+        assert_eq!(
+            Some(Win {
+                score: 8 * (45 - 15),
+                pattern: Pattern::Column(1)
+            }),
+            boards[0].draw(8)
+        );
+
+        // The last column of a 4 by 3 board.
+        assert_eq!(None, boards[1].draw(4));
+        assert_eq!(None, boards[1].draw(8));
+        assert_eq!(
+            Some(12 * (78 - 24)),
+            boards[1].draw(12).map(|win| win.score)
+        );
+
+        assert!(BoardParser::new("\n\n".lines(), 1, &WinRule::STANDARD)
+            .next()
+            .is_none());
+    }
+
+    #[test]
+    fn test_large_numbers() {
+        assert_eq!(
+            vec![300, 4_000_000_000],
+            parse_draw_numbers("300,4000000000").unwrap()
+        );
+
+        let mut board = Board::new(1, 2, vec![u32::MAX, u32::MAX], &WinRule::STANDARD);
+        assert_eq!(None, board.draw(7));
+        assert_eq!(
+            Some(u32::MAX as u128 * u32::MAX as u128),
+            board.draw(u32::MAX).map(|win| win.score)
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        fn error<T>(result: anyhow::Result<T>) -> String {
+            format!("{:#}", result.err().unwrap())
+        }
+
+        assert_eq!("Draw 2: Missing number", error(parse_draw_numbers("1,,3")));
+        assert_eq!(
+            "Draw 1: \"4294967296\" is above 4294967295",
+            error(parse_draw_numbers("4294967296"))
+        );
+        assert_eq!(
+            "Draw 3: \"-1\" is not a number",
+            error(parse_draw_numbers("1,2,-1"))
+        );
+
+        let board = |input: &str| {
+            BoardParser::new(input.lines(), 2, &WinRule::STANDARD)
+                .next()
+                .unwrap()
+        };
+        assert_eq!(
+            "Line 4: \"3 x\": \"x\" is not a number",
+            error(board("\n1 2\n3 x\n"))
+        );
+        assert_eq!(
+            "Line 3: expected 2 numbers, got 1",
+            error(board("1 2\n3\n"))
+        );
+    }
+
+    #[test]
+    fn test_large_board() {
+        // Marks beyond the first 64 cells.
+        let mut board = Board::new(10, 10, (0..100).collect(), &WinRule::STANDARD);
+        let column = (9..100).step_by(10).collect::<Vec<u32>>();
+        let scores = column
+            .iter()
+            .map(|&x| board.draw(x).map(|win| win.score))
+            .collect::<Vec<_>>();
+
+        let sum = (0..100).sum::<u128>() - column.iter().map(|&x| x as u128).sum::<u128>();
+        assert!(scores[..9].iter().all(Option::is_none));
+        assert_eq!(Some(99 * sum), scores[9]);
+
+        let mut board = Board::new(10, 10, (0..100).collect(), &WinRule::STANDARD);
+        assert!((90..99).all(|x| board.draw(x).is_none()));
+        assert!(board.draw(99).is_some());
+    }
+}
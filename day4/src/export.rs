@@ -0,0 +1,73 @@
+use std::io::Write;
+
+use crate::Timeline;
+
+/// One row per board in input order, boards that never won have empty columns.
+pub fn write_table(timeline: &Timeline, out: &mut impl Write) -> std::io::Result<()> {
+    writeln!(
+        out,
+        "{:>5}  {:>4}  {:>4}  {:>6}  {:>8}  pattern",
+        "board", "rank", "turn", "number", "score"
+    )?;
+
+    for outcome in &timeline.outcomes {
+        match &outcome.win {
+            Some(win) => writeln!(
+                out,
+                "{:>5}  {:>4}  {:>4}  {:>6}  {:>8}  {}",
+                outcome.board, win.rank, win.turn, win.number, win.score, win.pattern
+            )?,
+            None => writeln!(
+                out,
+                "{:>5}  {:>4}  {:>4}  {:>6}  {:>8}  -",
+                outcome.board, "-", "-", "-", "-"
+            )?,
+        }
+    }
+
+    Ok(())
+}
+
+pub fn write_json(timeline: &Timeline, out: &mut impl Write) -> anyhow::Result<()> {
+    serde_json::to_writer_pretty(&mut *out, &timeline.outcomes)?;
+    writeln!(out)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::export::*;
+    use crate::{Game, WinRule};
+
+    #[test]
+    fn test_export() {
+        let game = Game::parse("1,2\n\n1 2\n3 4\n\n5 6\n7 8\n", &WinRule::STANDARD).unwrap();
+        let timeline = game.play();
+
+        let mut out = Vec::new();
+        write_table(&timeline, &mut out).unwrap();
+        assert_eq!(
+            concat!(
+                "board  rank  turn  number     score  pattern\n",
+                "    1     1     2       2        14  row 1\n",
+                "    2     -     -       -         -  -\n",
+            ),
+            String::from_utf8(out).unwrap()
+        );
+
+        let mut out = Vec::new();
+        write_json(&timeline, &mut out).unwrap();
+        let json = serde_json::from_slice::<serde_json::Value>(&out).unwrap();
+        assert_eq!(
+            serde_json::json!([
+                {
+                    "board": 1,
+                    "win": { "rank": 1, "turn": 2, "number": 2, "score": 14, "pattern": "row 1" }
+                },
+                { "board": 2, "win": null }
+            ]),
+            json
+        );
+    }
+}
@@ -0,0 +1,181 @@
+use anyhow::{anyhow, Context};
+use serde::Serialize;
+
+use crate::{parse_draw_numbers, Board, BoardParser, Pattern, WinRule};
+
+/// Boards and the numbers drawn for them.
+#[derive(Debug, Clone)]
+pub struct Game {
+    numbers: Vec<u32>,
+    boards: Vec<Board>,
+}
+
+impl Game {
+    pub fn new(numbers: Vec<u32>, boards: Vec<Board>) -> Self {
+        Self { numbers, boards }
+    }
+
+    /// The draw numbers come on the first line, boards follow separated by empty lines.
+    pub fn parse(input: &str, rules: &[WinRule]) -> anyhow::Result<Self> {
+        let mut lines = input.lines();
+        let numbers = lines
+            .next()
+            .ok_or_else(|| anyhow!("Missing draw numbers"))
+            .and_then(|line| {
+                parse_draw_numbers(line).with_context(|| format!("Line 1: {:?}", line))
+            })?;
+
+        let boards = BoardParser::new(lines, 2, rules).collect::<anyhow::Result<Vec<_>>>()?;
+
+        Ok(Self::new(numbers, boards))
+    }
+
+    pub fn numbers(&self) -> &[u32] {
+        &self.numbers
+    }
+
+    pub fn boards(&self) -> &[Board] {
+        &self.boards
+    }
+
+    /// Draws every number and records when each board wins.
+    pub fn play(&self) -> Timeline {
+        let mut boards = self.boards.clone();
+        let mut outcomes = (1..=boards.len())
+            .map(|board| Outcome { board, win: None })
+            .collect::<Vec<_>>();
+        let mut rank = 0;
+
+        for (turn, &number) in (1..).zip(&self.numbers) {
+            for (board, outcome) in boards.iter_mut().zip(outcomes.iter_mut()) {
+                if board.score().is_some() {
+                    continue;
+                }
+
+                if let Some(win) = board.draw(number) {
+                    rank += 1;
+                    outcome.win = Some(Placing {
+                        rank,
+                        turn,
+                        number,
+                        score: win.score,
+                        pattern: win.pattern,
+                    });
+                }
+            }
+        }
+
+        Timeline { outcomes }
+    }
+}
+
+/// The outcome of every board of a game, in input order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Timeline {
+    pub outcomes: Vec<Outcome>,
+}
+
+impl Timeline {
+    /// Boards that won, in the order they did. Boards completed by the same draw are ranked
+    /// in input order.
+    pub fn winners(&self) -> Vec<&Outcome> {
+        let mut winners = self
+            .outcomes
+            .iter()
+            .filter(|outcome| outcome.win.is_some())
+            .collect::<Vec<_>>();
+        winners.sort_by_key(|outcome| outcome.win.as_ref().map(|win| win.rank));
+        winners
+    }
+
+    /// The board that won `rank`-th, counted from one.
+    pub fn winner(&self, rank: usize) -> Option<&Outcome> {
+        self.outcomes
+            .iter()
+            .find(|outcome| outcome.win.as_ref().is_some_and(|win| win.rank == rank))
+    }
+
+    pub fn never_won(&self) -> impl Iterator<Item = &Outcome> {
+        self.outcomes.iter().filter(|outcome| outcome.win.is_none())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Outcome {
+    /// Position of the board in the input, counted from one.
+    pub board: usize,
+    pub win: Option<Placing>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Placing {
+    /// Counted from one.
+    pub rank: usize,
+    /// The number of draws up to and including the winning one.
+    pub turn: usize,
+    pub number: u32,
+    pub score: u128,
+    pub pattern: Pattern,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::*;
+
+    const TEST_DATA: &str = "3,1,2,4,5\n\n1 2\n3 9\n\n3 4\n1 5\n\n7 8\n9 7\n";
+
+    #[test]
+    fn test_timeline() {
+        let game = Game::parse(TEST_DATA, &WinRule::STANDARD).unwrap();
+        assert_eq!(vec![3, 1, 2, 4, 5], game.numbers());
+        assert_eq!(3, game.boards().len());
+
+        let timeline = game.play();
+
+        // Both boards complete on the second draw, they rank in input order.
+        assert_eq!(
+            Some(Placing {
+                rank: 1,
+                turn: 2,
+                number: 1,
+                score: 2 + 9,
+                pattern: Pattern::Column(0)
+            }),
+            timeline.outcomes[0].win
+        );
+        assert_eq!(
+            Some(Placing {
+                rank: 2,
+                turn: 2,
+                number: 1,
+                score: 4 + 5,
+                pattern: Pattern::Column(0)
+            }),
+            timeline.outcomes[1].win
+        );
+
+        assert_eq!(
+            vec![1, 2],
+            timeline
+                .winners()
+                .iter()
+                .map(|outcome| outcome.board)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(Some(2), timeline.winner(2).map(|outcome| outcome.board));
+        assert_eq!(None, timeline.winner(3));
+        assert_eq!(
+            vec![3],
+            timeline
+                .never_won()
+                .map(|outcome| outcome.board)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Game::parse("", &WinRule::STANDARD).is_err());
+        assert!(Game::parse("1,x\n\n1 2\n3 4\n", &WinRule::STANDARD).is_err());
+    }
+}
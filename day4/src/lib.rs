@@ -0,0 +1,21 @@
+mod board;
+mod export;
+mod game;
+mod rigging;
+mod rules;
+mod server;
+mod simulation;
+mod solver;
+mod tui;
+
+pub use board::*;
+pub use export::*;
+pub use game::*;
+pub use rigging::*;
+pub use rules::*;
+pub use server::*;
+pub use simulation::*;
+pub use solver::*;
+pub use tui::*;
+
+pub const INPUT: &str = include_str!("input.txt");
@@ -0,0 +1,133 @@
+use std::collections::HashSet;
+
+use anyhow::bail;
+
+use crate::{Bitset, Board, Game, Pattern};
+
+/// Draws that make a board win before any other.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rigging {
+    pub pattern: Pattern,
+    /// In any order, the board wins on the last one.
+    pub numbers: Vec<u32>,
+}
+
+/// Finds the shortest sequence of numbers from the draw pool of `game` that completes `board`,
+/// counted from one, while every other board is still incomplete. `None` proves there is none.
+///
+/// Marks only accumulate, so a board complete after some draws stays complete after any more.
+/// A winning sequence therefore contains the numbers of some pattern of `board`, and those
+/// numbers alone win too. It is enough to check every pattern once.
+pub fn rig_draws(game: &Game, board: usize) -> anyhow::Result<Option<Rigging>> {
+    let target = match board
+        .checked_sub(1)
+        .and_then(|index| game.boards().get(index))
+    {
+        Some(target) => target,
+        None => bail!("There is no board {}", board),
+    };
+    let pool = game.numbers().iter().collect::<HashSet<_>>();
+
+    let mut candidates = target
+        .patterns()
+        .iter()
+        .filter_map(|(pattern, mask)| {
+            pattern_numbers(target, mask).map(|numbers| (pattern, numbers))
+        })
+        .filter(|(_, numbers)| numbers.iter().all(|number| pool.contains(number)))
+        .collect::<Vec<_>>();
+    candidates.sort_by_key(|(_, numbers)| numbers.len());
+
+    let others = game
+        .boards()
+        .iter()
+        .enumerate()
+        .filter(|&(index, _)| index + 1 != board)
+        .map(|(_, other)| other)
+        .collect::<Vec<_>>();
+
+    Ok(candidates
+        .into_iter()
+        .find(|(_, numbers)| {
+            let drawn = numbers.iter().collect::<HashSet<_>>();
+
+            others.iter().all(|other| {
+                other.patterns().iter().all(|(_, mask)| {
+                    pattern_numbers(other, mask)
+                        .is_none_or(|numbers| !numbers.iter().all(|number| drawn.contains(number)))
+                })
+            })
+        })
+        .map(|(pattern, numbers)| Rigging {
+            pattern: pattern.clone(),
+            numbers,
+        }))
+}
+
+/// The distinct numbers that mark the cells of `mask`, in cell order. A number repeated on
+/// a board only marks its first cell, so patterns holding a later copy never complete.
+fn pattern_numbers(board: &Board, mask: &Bitset) -> Option<Vec<u32>> {
+    let values = board.values();
+    let mut numbers = Vec::new();
+
+    for pos in mask.positions() {
+        let value = values[pos];
+        if values.iter().position(|&v| v == value) != Some(pos) {
+            return None;
+        }
+        if !numbers.contains(&value) {
+            numbers.push(value);
+        }
+    }
+
+    Some(numbers)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rigging::*;
+    use crate::{WinRule, INPUT};
+
+    #[test]
+    fn test_rig_draws() {
+        let game = Game::parse(INPUT, &WinRule::STANDARD).unwrap();
+
+        for board in 1..=game.boards().len() {
+            let rigging = rig_draws(&game, board).unwrap().unwrap();
+            assert_eq!(5, rigging.numbers.len());
+
+            let timeline = Game::new(rigging.numbers, game.boards().to_vec()).play();
+            let winners = timeline.winners();
+            assert_eq!(1, winners.len());
+            assert_eq!(board, winners[0].board);
+            assert_eq!(
+                Some(rigging.pattern),
+                winners[0].win.as_ref().map(|win| win.pattern.clone())
+            );
+        }
+
+        assert!(rig_draws(&game, 0).is_err());
+        assert!(rig_draws(&game, game.boards().len() + 1).is_err());
+    }
+
+    #[test]
+    fn test_rig_draws_impossible() {
+        // Every line of the first board completes a line of another board.
+        let input = "1,2,3,4,5\n\n1 2\n3 4\n\n1 2\n3 5\n\n3 4\n9 9\n\n2 9\n4 9\n";
+        let game = Game::parse(input, &WinRule::STANDARD).unwrap();
+        assert_eq!(None, rig_draws(&game, 1).unwrap());
+
+        // The bottom row and the right column of the second board are its own.
+        assert_eq!(
+            Some(Rigging {
+                pattern: Pattern::Row(1),
+                numbers: vec![3, 5]
+            }),
+            rig_draws(&game, 2).unwrap()
+        );
+
+        // Numbers outside the pool never get drawn.
+        let game = Game::parse("1,2\n\n1 3\n4 5\n", &WinRule::STANDARD).unwrap();
+        assert_eq!(None, rig_draws(&game, 1).unwrap());
+    }
+}
@@ -0,0 +1,290 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use anyhow::{bail, Context};
+use serde::{Serialize, Serializer};
+
+use crate::Bitset;
+
+/// A way for a board to win.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WinRule {
+    Rows,
+    Columns,
+    /// Either diagonal of a square board.
+    Diagonals,
+    Corners,
+    /// Both diagonals of a square board at once.
+    X,
+    Blackout,
+    /// Marked cells of a board of exactly this size. A rule without cells or with cells
+    /// outside the board never applies.
+    Custom {
+        width: usize,
+        height: usize,
+        cells: Vec<usize>,
+    },
+}
+
+impl WinRule {
+    pub const STANDARD: [WinRule; 2] = [WinRule::Rows, WinRule::Columns];
+
+    /// The patterns this rule accepts on a board of the given size.
+    pub fn patterns(&self, width: usize, height: usize) -> Vec<(Pattern, Bitset)> {
+        let len = width * height;
+        let diagonal = || Bitset::from_positions(len, (0..len).step_by(width + 1));
+        let anti_diagonal =
+            || Bitset::from_positions(len, (1..=width).map(|row| row * (width - 1)));
+
+        match self {
+            WinRule::Rows => (0..height)
+                .map(|row| {
+                    let cells = Bitset::from_positions(len, row * width..(row + 1) * width);
+                    (Pattern::Row(row), cells)
+                })
+                .collect(),
+            WinRule::Columns => (0..width)
+                .map(|col| {
+                    let cells = Bitset::from_positions(len, (col..len).step_by(width));
+                    (Pattern::Column(col), cells)
+                })
+                .collect(),
+            WinRule::Diagonals if width == height => vec![
+                (Pattern::Diagonal, diagonal()),
+                (Pattern::AntiDiagonal, anti_diagonal()),
+            ],
+            WinRule::Corners => vec![(
+                Pattern::Corners,
+                Bitset::from_positions(len, [0, width - 1, len - width, len - 1]),
+            )],
+            WinRule::X if width == height => {
+                vec![(Pattern::X, diagonal().union(&anti_diagonal()))]
+            }
+            WinRule::Blackout => vec![(Pattern::Blackout, Bitset::from_positions(len, 0..len))],
+            WinRule::Custom {
+                width: w,
+                height: h,
+                cells,
+            } if (*w, *h) == (width, height)
+                && !cells.is_empty()
+                && cells.iter().all(|&cell| cell < len) =>
+            {
+                vec![(
+                    Pattern::Custom(self.to_string()),
+                    Bitset::from_positions(len, cells.iter().copied()),
+                )]
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl FromStr for WinRule {
+    type Err = anyhow::Error;
+
+    /// A custom pattern lists its rows separated by `/`, `#` marks a cell and `.` skips it.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "rows" => WinRule::Rows,
+            "columns" => WinRule::Columns,
+            "diagonals" => WinRule::Diagonals,
+            "corners" => WinRule::Corners,
+            "x" => WinRule::X,
+            "blackout" => WinRule::Blackout,
+            pattern => {
+                let rows = pattern.split('/').collect::<Vec<_>>();
+                let width = rows[0].len();
+                if width == 0 || rows.iter().any(|row| row.len() != width) {
+                    bail!("Rows of pattern {:?} differ in length", pattern);
+                }
+
+                let cells = rows
+                    .concat()
+                    .chars()
+                    .enumerate()
+                    .filter_map(|(pos, c)| match c {
+                        '#' => Some(Ok(pos)),
+                        '.' => None,
+                        _ => Some(Err(c)),
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|c| anyhow::anyhow!("Invalid cell {:?}", c))
+                    .with_context(|| format!("Unknown rule {:?}", pattern))?;
+                if cells.is_empty() {
+                    bail!("Pattern {:?} marks no cells", pattern);
+                }
+
+                WinRule::Custom {
+                    width,
+                    height: rows.len(),
+                    cells,
+                }
+            }
+        })
+    }
+}
+
+impl Display for WinRule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WinRule::Rows => write!(f, "rows"),
+            WinRule::Columns => write!(f, "columns"),
+            WinRule::Diagonals => write!(f, "diagonals"),
+            WinRule::Corners => write!(f, "corners"),
+            WinRule::X => write!(f, "x"),
+            WinRule::Blackout => write!(f, "blackout"),
+            WinRule::Custom {
+                width,
+                height,
+                cells,
+            } => {
+                let rows = (0..*height)
+                    .map(|row| {
+                        (0..*width)
+                            .map(|col| match cells.contains(&(row * width + col)) {
+                                true => '#',
+                                false => '.',
+                            })
+                            .collect::<String>()
+                    })
+                    .collect::<Vec<_>>();
+                write!(f, "{}", rows.join("/"))
+            }
+        }
+    }
+}
+
+/// The cells that completed a board.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Pattern {
+    Row(usize),
+    Column(usize),
+    Diagonal,
+    AntiDiagonal,
+    Corners,
+    X,
+    Blackout,
+    Custom(String),
+}
+
+impl Display for Pattern {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Pattern::Row(row) => write!(f, "row {}", row + 1),
+            Pattern::Column(col) => write!(f, "column {}", col + 1),
+            Pattern::Diagonal => write!(f, "diagonal"),
+            Pattern::AntiDiagonal => write!(f, "anti-diagonal"),
+            Pattern::Corners => write!(f, "corners"),
+            Pattern::X => write!(f, "x"),
+            Pattern::Blackout => write!(f, "blackout"),
+            Pattern::Custom(pattern) => write!(f, "pattern {}", pattern),
+        }
+    }
+}
+
+/// Serialized as its description.
+impl Serialize for Pattern {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Win {
+    pub score: u128,
+    pub pattern: Pattern,
+}
+
+impl Display for Win {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.score, self.pattern)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rules::*;
+    use crate::Board;
+
+    #[test]
+    fn test_win_rules() {
+        let board = |rules: &[WinRule]| Board::new(5, 5, (0..25).collect(), rules);
+        let play = |mut board: Board, draws: &[u32]| {
+            let mut wins = draws.iter().map(|&x| board.draw(x)).collect::<Vec<_>>();
+            assert!(wins[..draws.len() - 1].iter().all(Option::is_none));
+            wins.pop().flatten().map(|win| win.pattern)
+        };
+
+        let rules = [WinRule::Diagonals];
+        assert_eq!(None, play(board(&rules), &[0, 1, 2, 3, 4]));
+        assert_eq!(
+            Some(Pattern::Diagonal),
+            play(board(&rules), &[0, 6, 12, 18, 24])
+        );
+        assert_eq!(
+            Some(Pattern::AntiDiagonal),
+            play(board(&rules), &[4, 8, 12, 16, 20])
+        );
+
+        assert_eq!(
+            Some(Pattern::Corners),
+            play(board(&[WinRule::Corners]), &[0, 4, 20, 24])
+        );
+        assert_eq!(
+            Some(Pattern::X),
+            play(board(&[WinRule::X]), &[0, 6, 12, 18, 24, 4, 8, 16, 20])
+        );
+        assert_eq!(
+            Some(Pattern::Blackout),
+            play(board(&[WinRule::Blackout]), &(0..25).collect::<Vec<_>>())
+        );
+
+        // The first rule wins when a draw completes several patterns.
+        let rules = [WinRule::Diagonals, WinRule::Rows];
+        assert_eq!(
+            Some(Pattern::Diagonal),
+            play(board(&rules), &[0, 6, 12, 18, 20, 21, 22, 23, 24])
+        );
+    }
+
+    #[test]
+    fn test_custom_rule() {
+        let rule = ".#./###/.#.".parse::<WinRule>().unwrap();
+        assert_eq!(".#./###/.#.", rule.to_string());
+
+        let mut board = Board::new(3, 3, (1..10).collect(), std::slice::from_ref(&rule));
+        assert!([2, 4, 5, 6].iter().all(|&x| board.draw(x).is_none()));
+        assert_eq!(
+            Some(Win {
+                score: 8 * (1 + 3 + 7 + 9),
+                pattern: Pattern::Custom(rule.to_string())
+            }),
+            board.draw(8)
+        );
+
+        // Patterns of another size never match.
+        let mut board = Board::new(5, 5, (0..25).collect(), &[rule]);
+        assert!((0..25).all(|x| board.draw(x).is_none()));
+
+        assert!("rows".parse::<WinRule>().is_ok());
+        assert!("#./#".parse::<WinRule>().is_err());
+        assert!("#x#".parse::<WinRule>().is_err());
+        assert!("...".parse::<WinRule>().is_err());
+    }
+
+    #[test]
+    fn test_invalid_custom_rule() {
+        let custom = |cells: Vec<usize>| WinRule::Custom {
+            width: 3,
+            height: 3,
+            cells,
+        };
+
+        for rule in [custom(vec![]), custom(vec![0, 9]), custom(vec![0, 100])] {
+            assert!(rule.patterns(3, 3).is_empty());
+
+            let mut board = Board::new(3, 3, (1..10).collect(), &[rule]);
+            assert!((1..10).all(|x| board.draw(x).is_none()));
+        }
+    }
+}
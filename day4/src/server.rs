@@ -0,0 +1,470 @@
+//! A bingo game for several players over TCP, one text command per line.
+//!
+//! Server to client:
+//! - `WELCOME <player>` on connect, players are numbered from one in joining order.
+//! - `BOARD <width> <height> <numbers...>` the board of the player, row by row.
+//! - `DRAW <turn> <number>` every drawn number, players that join late get the earlier ones.
+//! - `WINNER <player> <score> <pattern>` to everyone once a claim holds.
+//! - `REJECTED <reason>` to a player whose claim does not hold.
+//! - `ERROR <reason>` to a player that sent something else.
+//! - `END` once every number is drawn or every player has won or left.
+//!
+//! A player that stops reading is dropped once a line cannot be written within the write
+//! timeout, so it cannot hold up the draws of the others.
+//!
+//! Client to server:
+//! - `BINGO` claims a win.
+//! - `QUIT` leaves the game.
+
+use std::io::{BufRead, BufReader, ErrorKind, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::bail;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::{Board, Pattern, WinRule};
+
+#[derive(Debug, Clone)]
+pub struct ServerConfig {
+    pub width: usize,
+    pub height: usize,
+    /// Numbers on boards and in the draw pool run from zero up to this.
+    pub max_number: u32,
+    /// Time between draws.
+    pub interval: Duration,
+    /// How long a line to a player may block before the player is dropped.
+    pub write_timeout: Duration,
+    /// Drawing starts once this many players have joined.
+    pub players: usize,
+    pub seed: u64,
+    pub rules: Vec<WinRule>,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            width: 5,
+            height: 5,
+            max_number: 99,
+            interval: Duration::from_secs(1),
+            write_timeout: Duration::from_secs(1),
+            players: 1,
+            seed: 0,
+            rules: WinRule::STANDARD.to_vec(),
+        }
+    }
+}
+
+/// A win claimed by a player and confirmed by the server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Claim {
+    pub player: usize,
+    /// The number of draws when the claim arrived.
+    pub turn: usize,
+    pub score: u128,
+    pub pattern: Pattern,
+}
+
+/// Hands out boards to clients on `listener` and draws numbers until the game is over.
+/// Returns the confirmed claims in the order they arrived.
+pub fn serve(listener: TcpListener, config: ServerConfig) -> anyhow::Result<Vec<Claim>> {
+    let cells = config.width * config.height;
+    if cells == 0 || cells as u64 > config.max_number as u64 + 1 {
+        bail!(
+            "A {}x{} board needs at least {} numbers",
+            config.width,
+            config.height,
+            cells
+        );
+    }
+    if config.players == 0 {
+        bail!("The game needs at least one player");
+    }
+
+    let mut rng = ChaCha8Rng::seed_from_u64(config.seed);
+    let mut pool = (0..=config.max_number).collect::<Vec<_>>();
+    pool.shuffle(&mut rng);
+
+    let state = Arc::new(Mutex::new(State {
+        players: Vec::new(),
+        drawn: Vec::new(),
+        claims: Vec::new(),
+        finished: false,
+        rng,
+    }));
+
+    listener.set_nonblocking(true)?;
+    let acceptor = {
+        let state = state.clone();
+        let config = config.clone();
+        thread::spawn(move || accept(listener, state, config))
+    };
+
+    while state.lock().unwrap().players.len() < config.players {
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    for number in pool {
+        state.lock().unwrap().draw(number);
+        thread::sleep(config.interval);
+
+        if state.lock().unwrap().is_over() {
+            break;
+        }
+    }
+
+    let claims = {
+        let mut state = state.lock().unwrap();
+        state.finish();
+        state.claims.clone()
+    };
+    acceptor.join().unwrap()?;
+
+    Ok(claims)
+}
+
+struct State {
+    players: Vec<Player>,
+    drawn: Vec<u32>,
+    claims: Vec<Claim>,
+    finished: bool,
+    rng: ChaCha8Rng,
+}
+
+struct Player {
+    board: Board,
+    /// `None` once the player has left.
+    out: Option<TcpStream>,
+    claimed: bool,
+}
+
+impl State {
+    fn join(&mut self, out: TcpStream, config: &ServerConfig) -> usize {
+        let cells = config.width * config.height;
+        let values = rand::seq::index::sample(&mut self.rng, config.max_number as usize + 1, cells)
+            .into_iter()
+            .map(|number| number as u32)
+            .collect::<Vec<_>>();
+
+        let mut board = Board::new(config.width, config.height, values, &config.rules);
+        for &number in &self.drawn {
+            board.draw(number);
+        }
+
+        let numbers = board
+            .values()
+            .iter()
+            .map(u32::to_string)
+            .collect::<Vec<_>>()
+            .join(" ");
+        let history = (1..)
+            .zip(&self.drawn)
+            .map(|(turn, number)| format!("DRAW {} {}", turn, number))
+            .collect::<Vec<_>>();
+
+        self.players.push(Player {
+            board,
+            out: Some(out),
+            claimed: false,
+        });
+        let player = self.players.len();
+
+        self.send(player, &format!("WELCOME {}", player));
+        self.send(
+            player,
+            &format!("BOARD {} {} {}", config.width, config.height, numbers),
+        );
+        for line in history {
+            self.send(player, &line);
+        }
+
+        player
+    }
+
+    fn draw(&mut self, number: u32) {
+        self.drawn.push(number);
+        for player in &mut self.players {
+            player.board.draw(number);
+        }
+
+        let line = format!("DRAW {} {}", self.drawn.len(), number);
+        self.broadcast(&line);
+    }
+
+    fn claim(&mut self, player: usize) {
+        let entry = &self.players[player - 1];
+
+        let claim = match (entry.claimed, entry.board.win()) {
+            (true, _) => Err("already won"),
+            (false, None) => Err("the board is not complete"),
+            (false, Some(win)) => Ok(Claim {
+                player,
+                turn: self.drawn.len(),
+                score: win.score,
+                pattern: win.pattern.clone(),
+            }),
+        };
+
+        match claim {
+            Ok(claim) => {
+                self.players[player - 1].claimed = true;
+                let line = format!("WINNER {} {} {}", claim.player, claim.score, claim.pattern);
+                self.claims.push(claim);
+                self.broadcast(&line);
+            }
+            Err(reason) => self.send(player, &format!("REJECTED {}", reason)),
+        }
+    }
+
+    fn leave(&mut self, player: usize) {
+        if let Some(out) = self.players[player - 1].out.take() {
+            let _ = out.shutdown(Shutdown::Both);
+        }
+    }
+
+    /// Every player that is still connected has won.
+    fn is_over(&self) -> bool {
+        self.players
+            .iter()
+            .all(|player| player.claimed || player.out.is_none())
+    }
+
+    fn finish(&mut self) {
+        self.broadcast("END");
+        self.finished = true;
+
+        for player in 1..=self.players.len() {
+            self.leave(player);
+        }
+    }
+
+    /// Players whose connection fails or times out are treated as gone.
+    fn send(&mut self, player: usize, line: &str) {
+        let out = &mut self.players[player - 1].out;
+        if let Some(stream) = out {
+            if writeln!(stream, "{}", line).is_err() {
+                self.leave(player);
+            }
+        }
+    }
+
+    fn broadcast(&mut self, line: &str) {
+        for player in 1..=self.players.len() {
+            self.send(player, line);
+        }
+    }
+}
+
+fn accept(
+    listener: TcpListener,
+    state: Arc<Mutex<State>>,
+    config: ServerConfig,
+) -> anyhow::Result<()> {
+    while !state.lock().unwrap().finished {
+        let stream = match listener.accept() {
+            Ok((stream, _)) => stream,
+            Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                thread::sleep(Duration::from_millis(10));
+                continue;
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        stream.set_nonblocking(false)?;
+        stream.set_write_timeout(Some(config.write_timeout))?;
+        let player = {
+            let mut state = state.lock().unwrap();
+            if state.finished {
+                break;
+            }
+            state.join(stream.try_clone()?, &config)
+        };
+
+        let state = state.clone();
+        thread::spawn(move || play(stream, player, state));
+    }
+
+    Ok(())
+}
+
+/// Handles the commands of a single player until it leaves or the game is over.
+fn play(stream: TcpStream, player: usize, state: Arc<Mutex<State>>) {
+    for line in BufReader::new(stream).lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(_) => break,
+        };
+
+        let mut state = state.lock().unwrap();
+        match line.trim() {
+            "BINGO" => state.claim(player),
+            "QUIT" => break,
+            "" => {}
+            command => state.send(player, &format!("ERROR unknown command {:?}", command)),
+        }
+    }
+
+    state.lock().unwrap().leave(player);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+
+    use socket2::{Domain, Socket, Type};
+
+    use crate::server::*;
+
+    /// Plays honestly after an optional early claim, returns the server lines it received
+    /// along with its player number and the score of its own copy of the board.
+    fn client(addr: SocketAddr, cheat: bool) -> (usize, Option<u128>, Vec<String>) {
+        let stream = TcpStream::connect(addr).unwrap();
+        let mut out = stream.try_clone().unwrap();
+        let mut lines = BufReader::new(stream).lines().map(Result::unwrap);
+
+        let welcome = lines.next().unwrap();
+        let player = welcome.strip_prefix("WELCOME ").unwrap().parse().unwrap();
+
+        let board = lines.next().unwrap();
+        let fields = board
+            .split(' ')
+            .skip(1)
+            .map(|field| field.parse().unwrap())
+            .collect::<Vec<u32>>();
+        let mut board = Board::new(
+            fields[0] as usize,
+            fields[1] as usize,
+            fields[2..].to_vec(),
+            &WinRule::STANDARD,
+        );
+
+        if cheat {
+            writeln!(out, "BINGO").unwrap();
+        }
+
+        let mut received = Vec::new();
+        for line in lines {
+            if let Some(draw) = line.strip_prefix("DRAW ") {
+                let number = draw.split(' ').nth(1).unwrap().parse().unwrap();
+                if board.score().is_none() && board.draw(number).is_some() {
+                    writeln!(out, "BINGO").unwrap();
+                }
+            }
+
+            let end = line == "END";
+            received.push(line);
+            if end {
+                break;
+            }
+        }
+
+        (player, board.score(), received)
+    }
+
+    #[test]
+    fn test_serve() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let config = ServerConfig {
+            interval: Duration::from_millis(5),
+            players: 2,
+            seed: 42,
+            ..ServerConfig::default()
+        };
+        let server = thread::spawn(move || serve(listener, config));
+
+        let clients = [false, true].map(|cheat| thread::spawn(move || client(addr, cheat)));
+        let clients = clients.map(|client| client.join().unwrap());
+        let claims = server.join().unwrap().unwrap();
+
+        assert_eq!(2, claims.len());
+        for (player, score, received) in &clients {
+            let claim = claims.iter().find(|claim| claim.player == *player).unwrap();
+            assert_eq!(*score, Some(claim.score));
+
+            let winners = received
+                .iter()
+                .filter(|line| line.starts_with("WINNER "))
+                .cloned()
+                .collect::<Vec<_>>();
+            assert_eq!(
+                claims
+                    .iter()
+                    .map(|claim| format!(
+                        "WINNER {} {} {}",
+                        claim.player, claim.score, claim.pattern
+                    ))
+                    .collect::<Vec<_>>(),
+                winners
+            );
+            assert_eq!(Some(&"END".to_string()), received.last());
+        }
+
+        let (_, _, cheater) = &clients[1];
+        assert!(cheater.contains(&"REJECTED the board is not complete".to_string()));
+        assert!(!clients[0].2.iter().any(|line| line.starts_with("REJECTED")));
+    }
+
+    #[test]
+    fn test_serve_slow_player() {
+        // Accepted streams inherit the small send buffer, so with a small receive buffer too
+        // a player that never reads blocks the server after a few hundred draws.
+        let socket = Socket::new(Domain::IPV4, Type::STREAM, None).unwrap();
+        socket.set_send_buffer_size(4096).unwrap();
+        socket
+            .bind(&SocketAddr::from(([127, 0, 0, 1], 0)).into())
+            .unwrap();
+        socket.listen(8).unwrap();
+        let listener = TcpListener::from(socket);
+        let addr = listener.local_addr().unwrap();
+
+        let config = ServerConfig {
+            interval: Duration::ZERO,
+            write_timeout: Duration::from_millis(200),
+            max_number: 9_999,
+            players: 2,
+            ..ServerConfig::default()
+        };
+        let server = thread::spawn(move || serve(listener, config));
+
+        let stalled = Socket::new(Domain::IPV4, Type::STREAM, None).unwrap();
+        stalled.set_recv_buffer_size(4096).unwrap();
+        stalled.connect(&addr.into()).unwrap();
+        let (player, score, received) = client(addr, false);
+        let claims = server.join().unwrap().unwrap();
+
+        assert_eq!(1, claims.len());
+        assert_eq!((player, score), (claims[0].player, Some(claims[0].score)));
+        assert_eq!(Some(&"END".to_string()), received.last());
+
+        // The stalled player was cut off long before the end of the game.
+        let stalled = BufReader::new(TcpStream::from(stalled))
+            .lines()
+            .map_while(Result::ok)
+            .collect::<Vec<_>>();
+        assert!(stalled.len() < received.len() / 2);
+        assert!(!stalled.contains(&"END".to_string()));
+    }
+
+    #[test]
+    fn test_serve_invalid_config() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let config = ServerConfig {
+            max_number: 10,
+            ..ServerConfig::default()
+        };
+        assert!(serve(listener, config).is_err());
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let config = ServerConfig {
+            players: 0,
+            ..ServerConfig::default()
+        };
+        assert!(serve(listener, config).is_err());
+    }
+}
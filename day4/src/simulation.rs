@@ -0,0 +1,140 @@
+use std::collections::HashSet;
+use std::io::Write;
+
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::Game;
+
+/// How a board fares over many random draw orders.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoardOdds {
+    /// Position of the board in the input, counted from one.
+    pub board: usize,
+    /// Share of trials in which no board wins before this one.
+    pub win_probability: f64,
+    /// Mean win turn over the trials in which the board wins at all.
+    pub expected_win_turn: Option<f64>,
+    /// Share of trials in which no board wins after this one.
+    pub last_probability: f64,
+}
+
+/// Plays `trials` games with the draw pool of `game` shuffled by a generator seeded with `seed`,
+/// so the same seed always gives the same odds. Boards completed by the same draw share it,
+/// every one of them counts as winning first or last.
+pub fn simulate(game: &Game, trials: usize, seed: u64) -> Vec<BoardOdds> {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let mut seen = HashSet::new();
+    let mut pool = game
+        .numbers()
+        .iter()
+        .copied()
+        .filter(|&number| seen.insert(number))
+        .collect::<Vec<_>>();
+
+    let boards = game.boards().len();
+    let mut firsts = vec![0_usize; boards];
+    let mut lasts = vec![0_usize; boards];
+    let mut wins = vec![0_usize; boards];
+    let mut turns = vec![0_usize; boards];
+
+    for _ in 0..trials {
+        pool.shuffle(&mut rng);
+        let timeline = Game::new(pool.clone(), game.boards().to_vec()).solve();
+
+        let win_turns = timeline
+            .outcomes
+            .iter()
+            .map(|outcome| outcome.win.as_ref().map(|win| win.turn))
+            .collect::<Vec<_>>();
+        let first = win_turns.iter().flatten().min();
+        let last = win_turns.iter().flatten().max();
+
+        for (board, turn) in win_turns.iter().enumerate() {
+            if let Some(turn) = turn {
+                wins[board] += 1;
+                turns[board] += turn;
+                firsts[board] += (Some(turn) == first) as usize;
+                lasts[board] += (Some(turn) == last) as usize;
+            }
+        }
+    }
+
+    let share = |count: usize| match trials {
+        0 => 0.0,
+        trials => count as f64 / trials as f64,
+    };
+
+    (0..boards)
+        .map(|board| BoardOdds {
+            board: board + 1,
+            win_probability: share(firsts[board]),
+            expected_win_turn: (wins[board] > 0).then(|| turns[board] as f64 / wins[board] as f64),
+            last_probability: share(lasts[board]),
+        })
+        .collect()
+}
+
+pub fn write_odds(odds: &[BoardOdds], out: &mut impl Write) -> std::io::Result<()> {
+    writeln!(
+        out,
+        "{:>5}  {:>8}  {:>9}  {:>8}",
+        "board", "win", "mean turn", "last"
+    )?;
+
+    for board in odds {
+        let turn = board
+            .expected_win_turn
+            .map(|turn| format!("{:.2}", turn))
+            .unwrap_or_else(|| "-".to_string());
+
+        writeln!(
+            out,
+            "{:>5}  {:>8.4}  {:>9}  {:>8.4}",
+            board.board, board.win_probability, turn, board.last_probability
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::simulation::*;
+    use crate::WinRule;
+
+    #[test]
+    fn test_simulate() {
+        // The first board needs one number, the second needs two and the third can never win.
+        let input = "1,2,3,4\n\n1\n\n2 3\n7 8\n\n9\n";
+        let game = Game::parse(input, &WinRule::STANDARD).unwrap();
+
+        let odds = simulate(&game, 1000, 7);
+        assert_eq!(odds, simulate(&game, 1000, 7));
+        assert_eq!(3, odds.len());
+
+        // 1 comes after both 2 and 3 in a third of the draw orders.
+        assert!((odds[0].win_probability - 2.0 / 3.0).abs() < 0.05);
+        assert!((odds[0].last_probability - 1.0 / 3.0).abs() < 0.05);
+        assert!((odds[0].win_probability + odds[1].win_probability - 1.0).abs() < 1e-9);
+        assert!((odds[0].expected_win_turn.unwrap() - 2.5).abs() < 0.1);
+
+        assert_eq!(0.0, odds[2].win_probability);
+        assert_eq!(None, odds[2].expected_win_turn);
+    }
+
+    #[test]
+    fn test_simulate_no_trials() {
+        let game = Game::parse("1\n\n1\n", &WinRule::STANDARD).unwrap();
+        assert_eq!(
+            vec![BoardOdds {
+                board: 1,
+                win_probability: 0.0,
+                expected_win_turn: None,
+                last_probability: 0.0
+            }],
+            simulate(&game, 0, 0)
+        );
+    }
+}
@@ -0,0 +1,149 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{Board, Game, Outcome, Pattern, Placing, Timeline};
+
+impl Game {
+    /// Finds the same outcomes as `play` without replaying the draws. Every cell gets the turn
+    /// its number is drawn, a pattern completes on the latest turn of its cells and a board
+    /// wins with its earliest pattern.
+    pub fn solve(&self) -> Timeline {
+        let turns = draw_turns(self.numbers());
+
+        let mut wins = self
+            .boards()
+            .iter()
+            .enumerate()
+            .filter_map(|(index, board)| {
+                win_turn(board, &turns).map(|(turn, pattern)| (turn, index, pattern))
+            })
+            .collect::<Vec<_>>();
+        wins.sort_unstable_by_key(|&(turn, index, _)| (turn, index));
+
+        let mut outcomes = (1..=self.boards().len())
+            .map(|board| Outcome { board, win: None })
+            .collect::<Vec<_>>();
+
+        for (rank, (turn, index, pattern)) in (1..).zip(wins) {
+            let board = &self.boards()[index];
+            let number = self.numbers()[turn - 1];
+            let unmarked = cell_turns(board, &turns)
+                .into_iter()
+                .zip(board.values())
+                .filter(|(cell, _)| cell.is_none_or(|cell| cell > turn))
+                .map(|(_, &value)| value as u128)
+                .sum::<u128>();
+
+            outcomes[index].win = Some(Placing {
+                rank,
+                turn,
+                number,
+                score: number as u128 * unmarked,
+                pattern: pattern.clone(),
+            });
+        }
+
+        Timeline { outcomes }
+    }
+}
+
+/// The turn each number is first drawn on, counted from one.
+pub fn draw_turns(numbers: &[u32]) -> HashMap<u32, usize> {
+    let mut turns = HashMap::with_capacity(numbers.len());
+    for (turn, &number) in (1..).zip(numbers) {
+        turns.entry(number).or_insert(turn);
+    }
+    turns
+}
+
+/// The turn a board wins on and the pattern it wins with, if it ever does. Like `play`, a
+/// board is only checked on turns that mark one of its cells and cells outside the board are
+/// never marked.
+pub fn win_turn<'a>(board: &'a Board, turns: &HashMap<u32, usize>) -> Option<(usize, &'a Pattern)> {
+    let cells = cell_turns(board, turns);
+    let first = cells.iter().flatten().copied().min()?;
+
+    board
+        .patterns()
+        .iter()
+        .filter_map(|(pattern, mask)| {
+            mask.positions()
+                .map(|pos| cells.get(pos).copied().flatten())
+                .try_fold(first, |latest, turn| turn.map(|turn| latest.max(turn)))
+                .map(|turn| (turn, pattern))
+        })
+        .min_by_key(|&(turn, _)| turn)
+}
+
+/// The turn every cell is marked on. A number repeated on a board only marks its first cell.
+fn cell_turns(board: &Board, turns: &HashMap<u32, usize>) -> Vec<Option<usize>> {
+    let mut seen = HashSet::with_capacity(board.values().len());
+
+    board
+        .values()
+        .iter()
+        .map(|value| match seen.insert(value) {
+            true => turns.get(value).copied(),
+            false => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::solver::*;
+    use crate::{WinRule, INPUT};
+
+    #[test]
+    fn test_solve() {
+        let rules = [
+            vec![WinRule::Rows, WinRule::Columns],
+            vec![WinRule::Diagonals, WinRule::Corners, WinRule::Rows],
+            vec![WinRule::X, WinRule::Blackout],
+            vec![
+                "#...#/.#.#./..#../.#.#./#...#".parse().unwrap(),
+                "..#../..#../#####/..#../..#..".parse().unwrap(),
+            ],
+            vec![
+                WinRule::Custom {
+                    width: 5,
+                    height: 5,
+                    cells: vec![],
+                },
+                WinRule::Custom {
+                    width: 5,
+                    height: 5,
+                    cells: vec![0, 1, 100],
+                },
+                WinRule::Corners,
+            ],
+        ];
+
+        for rules in rules {
+            let game = Game::parse(INPUT, &rules).unwrap();
+            assert_eq!(game.play(), game.solve());
+        }
+    }
+
+    #[test]
+    fn test_solve_edge_cases() {
+        // Repeated draws and board values, a board without a win and a simultaneous win.
+        let input = "5,1,5,2,3,4\n\n1 1\n2 3\n\n3 4\n1 2\n\n7 8\n9 6\n\n2 9\n1 9\n";
+        let game = Game::parse(input, &WinRule::STANDARD).unwrap();
+        assert_eq!(game.play(), game.solve());
+    }
+
+    #[test]
+    fn test_win_turn() {
+        let turns = draw_turns(&[4, 3, 4, 1, 2]);
+        assert_eq!(Some(&1), turns.get(&4));
+
+        let board = Board::new(2, 2, vec![1, 2, 3, 4], &WinRule::STANDARD);
+        assert_eq!(Some((2, &Pattern::Row(1))), win_turn(&board, &turns));
+
+        let board = Board::new(2, 2, vec![2, 1, 5, 6], &WinRule::STANDARD);
+        assert_eq!(Some((5, &Pattern::Row(0))), win_turn(&board, &turns));
+
+        let board = Board::new(2, 2, vec![6, 2, 7, 5], &WinRule::STANDARD);
+        assert_eq!(None, win_turn(&board, &turns));
+    }
+}
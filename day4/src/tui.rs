@@ -0,0 +1,421 @@
+//! Replays a game in the terminal.
+
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode};
+use crossterm::style::{
+    Attribute, Color, Print, SetAttribute, SetBackgroundColor, SetForegroundColor,
+};
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, queue};
+
+use crate::{Board, Game, Win};
+
+const MIN_DELAY: Duration = Duration::from_millis(25);
+const MAX_DELAY: Duration = Duration::from_millis(3200);
+/// How long and how fast the winning lines of a board flash.
+const FLASH: Duration = Duration::from_millis(1500);
+const BLINK: Duration = Duration::from_millis(150);
+const LEADERBOARD_WIDTH: u16 = 36;
+
+/// A game drawn one number at a time.
+pub struct Replay<'a> {
+    game: &'a Game,
+    boards: Vec<Board>,
+    turn: usize,
+    leaderboard: Vec<Standing>,
+    playing: bool,
+    delay: Duration,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Standing {
+    /// Position of the board in the input, counted from one.
+    pub board: usize,
+    pub turn: usize,
+    pub win: Win,
+}
+
+impl<'a> Replay<'a> {
+    pub fn new(game: &'a Game) -> Self {
+        Self {
+            game,
+            boards: game.boards().to_vec(),
+            turn: 0,
+            leaderboard: Vec::new(),
+            playing: false,
+            delay: Duration::from_millis(400),
+        }
+    }
+
+    pub fn boards(&self) -> &[Board] {
+        &self.boards
+    }
+
+    /// The number of draws so far.
+    pub fn turn(&self) -> usize {
+        self.turn
+    }
+
+    pub fn last_number(&self) -> Option<u32> {
+        self.turn
+            .checked_sub(1)
+            .map(|index| self.game.numbers()[index])
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.turn == self.game.numbers().len()
+    }
+
+    /// Boards in the order they won.
+    pub fn leaderboard(&self) -> &[Standing] {
+        &self.leaderboard
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    pub fn delay(&self) -> Duration {
+        self.delay
+    }
+
+    /// Draws the next number and returns the boards it completed, counted from one.
+    /// Playing stops after the last number.
+    pub fn step(&mut self) -> Vec<usize> {
+        let number = match self.game.numbers().get(self.turn) {
+            Some(&number) => number,
+            None => return Vec::new(),
+        };
+        self.turn += 1;
+
+        let mut completed = Vec::new();
+        for (index, board) in self.boards.iter_mut().enumerate() {
+            if board.score().is_some() {
+                continue;
+            }
+
+            if let Some(win) = board.draw(number) {
+                completed.push(index + 1);
+                self.leaderboard.push(Standing {
+                    board: index + 1,
+                    turn: self.turn,
+                    win,
+                });
+            }
+        }
+
+        if self.is_finished() {
+            self.playing = false;
+        }
+
+        completed
+    }
+
+    pub fn restart(&mut self) {
+        *self = Self {
+            delay: self.delay,
+            ..Self::new(self.game)
+        };
+    }
+
+    pub fn toggle(&mut self) {
+        self.playing = !self.playing && !self.is_finished();
+    }
+
+    pub fn faster(&mut self) {
+        self.delay = (self.delay / 2).max(MIN_DELAY);
+    }
+
+    pub fn slower(&mut self) {
+        self.delay = (self.delay * 2).min(MAX_DELAY);
+    }
+}
+
+/// Takes over the terminal until the user quits.
+///
+/// Space plays or pauses, `n` or the right arrow draws a single number, `+` and `-` change
+/// the speed, the up and down arrows scroll the boards, `r` restarts and `q` quits.
+pub fn run_tui(game: &Game) -> anyhow::Result<()> {
+    let mut out = std::io::stdout();
+
+    terminal::enable_raw_mode()?;
+    execute!(out, EnterAlternateScreen, Hide)?;
+    let result = event_loop(&mut out, game);
+    execute!(out, Show, LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+
+    result
+}
+
+fn event_loop(out: &mut impl Write, game: &Game) -> anyhow::Result<()> {
+    let mut replay = Replay::new(game);
+    let mut scroll = 0;
+    let mut flash = (Vec::new(), Instant::now());
+    let mut next_draw = Instant::now();
+
+    loop {
+        let since = flash.1.elapsed();
+        let blinking =
+            match since < FLASH && (since.as_millis() / BLINK.as_millis()).is_multiple_of(2) {
+                true => &flash.0[..],
+                false => &[],
+            };
+        scroll = render(out, &replay, scroll, blinking)?;
+
+        let timeout = match replay.is_playing() {
+            true => next_draw
+                .saturating_duration_since(Instant::now())
+                .min(BLINK),
+            false => BLINK,
+        };
+
+        if event::poll(timeout)? {
+            if let Event::Key(key) = event::read()? {
+                match key.code {
+                    KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                    KeyCode::Char(' ') => {
+                        replay.toggle();
+                        next_draw = Instant::now();
+                    }
+                    KeyCode::Char('n') | KeyCode::Right => flash = (replay.step(), Instant::now()),
+                    KeyCode::Char('+') => replay.faster(),
+                    KeyCode::Char('-') => replay.slower(),
+                    KeyCode::Char('r') => {
+                        replay.restart();
+                        flash.0.clear();
+                    }
+                    KeyCode::Up => scroll = scroll.saturating_sub(1),
+                    KeyCode::Down => scroll += 1,
+                    _ => {}
+                }
+            }
+        }
+
+        if replay.is_playing() && Instant::now() >= next_draw {
+            let completed = replay.step();
+            if !completed.is_empty() {
+                flash = (completed, Instant::now());
+            }
+            next_draw = Instant::now() + replay.delay();
+        }
+    }
+}
+
+/// Draws a frame, boards are laid out in a grid scrolled by `scroll` grid rows next to the
+/// leaderboard. Returns the scroll clamped to the grid.
+fn render(
+    out: &mut impl Write,
+    replay: &Replay,
+    scroll: usize,
+    blinking: &[usize],
+) -> anyhow::Result<usize> {
+    let (columns, rows) = terminal::size()?;
+    let boards = replay.boards();
+
+    let digits = boards
+        .iter()
+        .flat_map(|board| board.values())
+        .map(|value| value.to_string().len())
+        .max()
+        .unwrap_or(1);
+    let cell = digits as u16 + 1;
+    let board_width = boards.iter().map(Board::width).max().unwrap_or(0) as u16 * cell + 2;
+    let board_height = boards.iter().map(Board::height).max().unwrap_or(0) as u16 + 2;
+
+    let area = columns.saturating_sub(LEADERBOARD_WIDTH).max(board_width);
+    let per_row = (area / board_width).max(1) as usize;
+    let visible_rows = (rows.saturating_sub(3) / board_height).max(1) as usize;
+    let grid_rows = boards.len().div_ceil(per_row);
+    let scroll = scroll.min(grid_rows.saturating_sub(visible_rows));
+
+    queue!(out, Clear(ClearType::All), MoveTo(0, 0))?;
+    queue!(
+        out,
+        Print(format!(
+            "Turn {}/{}  Last draw: {}  Delay: {}ms  {}",
+            replay.turn(),
+            replay.game.numbers().len(),
+            replay
+                .last_number()
+                .map_or("-".to_string(), |number| number.to_string()),
+            replay.delay().as_millis(),
+            match (replay.is_finished(), replay.is_playing()) {
+                (true, _) => "[finished]",
+                (false, true) => "[playing]",
+                (false, false) => "[paused]",
+            }
+        ))
+    )?;
+
+    for (index, board) in boards.iter().enumerate().skip(scroll * per_row) {
+        let grid_row = index / per_row - scroll;
+        if grid_row >= visible_rows {
+            break;
+        }
+
+        let x = (index % per_row) as u16 * board_width;
+        let y = 2 + grid_row as u16 * board_height;
+        render_board(
+            out,
+            board,
+            index + 1,
+            (x, y),
+            cell,
+            blinking.contains(&(index + 1)),
+        )?;
+    }
+
+    let x = columns.saturating_sub(LEADERBOARD_WIDTH) + 1;
+    queue!(out, MoveTo(x, 2), Print("Leaderboard"))?;
+    for (line, standing) in replay
+        .leaderboard()
+        .iter()
+        .take(rows.saturating_sub(5) as usize)
+        .enumerate()
+    {
+        queue!(
+            out,
+            MoveTo(x, 3 + line as u16),
+            Print(format!(
+                "{:>3}. #{:<3} turn {:<3} {} ({})",
+                line + 1,
+                standing.board,
+                standing.turn,
+                standing.win.score,
+                standing.win.pattern
+            ))
+        )?;
+    }
+
+    queue!(
+        out,
+        MoveTo(0, rows.saturating_sub(1)),
+        Print("space play/pause  n step  +/- speed  up/down scroll  r restart  q quit")
+    )?;
+    out.flush()?;
+
+    Ok(scroll)
+}
+
+fn render_board(
+    out: &mut impl Write,
+    board: &Board,
+    number: usize,
+    (x, y): (u16, u16),
+    cell: u16,
+    blinking: bool,
+) -> anyhow::Result<()> {
+    let winning = board.win().and_then(|win| {
+        board
+            .patterns()
+            .iter()
+            .find(|(pattern, _)| *pattern == win.pattern)
+            .map(|(_, mask)| mask)
+    });
+
+    let title = match board.score() {
+        Some(score) => format!("#{} won {}", number, score),
+        None => format!("#{}", number),
+    };
+    let color = match board.score() {
+        Some(_) => Color::Green,
+        None => Color::Reset,
+    };
+    queue!(
+        out,
+        MoveTo(x, y),
+        SetForegroundColor(color),
+        Print(title),
+        SetForegroundColor(Color::Reset)
+    )?;
+
+    for (pos, value) in board.values().iter().enumerate() {
+        let row = (pos / board.width()) as u16;
+        let col = (pos % board.width()) as u16;
+        queue!(out, MoveTo(x + col * cell, y + 1 + row))?;
+
+        if blinking && winning.is_some_and(|mask| mask.contains(pos)) {
+            queue!(
+                out,
+                SetBackgroundColor(Color::Yellow),
+                SetForegroundColor(Color::Black)
+            )?;
+        } else if board.is_marked(pos) {
+            queue!(out, SetAttribute(Attribute::Reverse))?;
+        }
+
+        queue!(
+            out,
+            Print(format!("{:>width$}", value, width = cell as usize - 1)),
+            SetAttribute(Attribute::Reset),
+            SetBackgroundColor(Color::Reset),
+            SetForegroundColor(Color::Reset)
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tui::*;
+    use crate::{WinRule, INPUT};
+
+    #[test]
+    fn test_replay() {
+        let game = Game::parse(INPUT, &WinRule::STANDARD).unwrap();
+        let mut replay = Replay::new(&game);
+        assert_eq!(None, replay.last_number());
+
+        while !replay.is_finished() {
+            replay.step();
+        }
+        assert!(replay.step().is_empty());
+        assert_eq!(Some(*game.numbers().last().unwrap()), replay.last_number());
+
+        let timeline = game.play();
+        let winners = timeline.winners();
+        assert_eq!(winners.len(), replay.leaderboard().len());
+        for (outcome, standing) in winners.iter().zip(replay.leaderboard()) {
+            let win = outcome.win.as_ref().unwrap();
+            assert_eq!(outcome.board, standing.board);
+            assert_eq!(win.turn, standing.turn);
+            assert_eq!(win.score, standing.win.score);
+        }
+
+        replay.restart();
+        assert_eq!(0, replay.turn());
+        assert!(replay.leaderboard().is_empty());
+    }
+
+    #[test]
+    fn test_replay_controls() {
+        let game = Game::parse("1,2\n\n1 2\n3 4\n", &WinRule::STANDARD).unwrap();
+        let mut replay = Replay::new(&game);
+
+        replay.toggle();
+        assert!(replay.is_playing());
+        assert!(replay.step().is_empty());
+        assert_eq!(vec![1], replay.step());
+        assert!(!replay.is_playing());
+
+        // A finished replay does not start playing.
+        replay.toggle();
+        assert!(!replay.is_playing());
+
+        for _ in 0..10 {
+            replay.faster();
+        }
+        assert_eq!(MIN_DELAY, replay.delay());
+        for _ in 0..10 {
+            replay.slower();
+        }
+        assert_eq!(MAX_DELAY, replay.delay());
+
+        replay.restart();
+        assert_eq!(MAX_DELAY, replay.delay());
+    }
+}
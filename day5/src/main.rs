@@ -1,29 +1,79 @@
-#![feature(step_trait)]
-
 use std::collections::HashMap;
 use std::str::Lines;
 
-fn main() -> anyhow::Result<()> {
-    let input = include_str!("input.txt");
+use anyhow::bail;
+
+mod sweep;
 
-    let mut overlaps: HashMap<Point, usize> = HashMap::new();
-    Parser::new(input.lines())
-        // uncomment for part 1
-        // .filter(|r| match r.orientation() {
-        //     Orientation::Horizontal => true,
-        //     Orientation::Vertical => true,
-        //     Orientation::Other => false,
-        // })
-        .flat_map(|r| r.iter())
-        .for_each(|p| *overlaps.entry(p).or_insert(0) += 1);
+const INPUT: &str = include_str!("input.txt");
+
+/// Usage: `day5 [--all] [--bresenham]`.
+/// `--all` also counts the lines at other angles by the points they pass through exactly,
+/// `--bresenham` counts them by the points Bresenham's algorithm draws instead and implies `--all`.
+fn main() -> anyhow::Result<()> {
+    let mut all = None;
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--all" => {
+                all.get_or_insert(Rasterization::Exact);
+            }
+            "--bresenham" => all = Some(Rasterization::Bresenham),
+            _ => bail!("Usage: day5 [--all] [--bresenham]"),
+        }
+    }
 
-    let overlaps = overlaps.iter().filter(|(_, &count)| count >= 2).count();
+    let ranges = Parser::new(INPUT.lines()).collect::<Vec<_>>();
 
-    println!("Overlaps: {}", overlaps);
+    println!(
+        "Overlaps of horizontal and vertical lines: {}",
+        count_overlaps(&ranges, LineFilter::AxisAligned, Rasterization::Exact)?
+    );
+    println!(
+        "Overlaps including diagonals: {}",
+        count_overlaps(&ranges, LineFilter::WithDiagonals, Rasterization::Exact)?
+    );
+    if let Some(rasterization) = all {
+        println!(
+            "Overlaps of all lines: {}",
+            count_overlaps(&ranges, LineFilter::All, rasterization)?
+        );
+    }
 
     Ok(())
 }
 
+/// The number of points covered by at least two of the ranges the filter accepts. Sloped
+/// ranges are counted point by point as the rasterization gives them, the rest by a sweep.
+fn count_overlaps(
+    ranges: &[PointRange],
+    filter: LineFilter,
+    rasterization: Rasterization,
+) -> anyhow::Result<usize> {
+    let (sloped, straight): (Vec<PointRange>, Vec<PointRange>) = ranges
+        .iter()
+        .filter(|r| filter.accepts(r))
+        .partition(|r| r.orientation() == Orientation::Other);
+
+    let coverage = sweep::Coverage::new(&straight)?;
+
+    let mut points: HashMap<Point, usize> = HashMap::new();
+    sloped
+        .iter()
+        .flat_map(|r| r.iter(rasterization))
+        .for_each(|p| *points.entry(p).or_insert(0) += 1);
+
+    // Points the straight ranges cover twice are counted by the sweep already.
+    let sloped_overlaps = points
+        .into_iter()
+        .filter(|&(point, count)| {
+            let depth = coverage.depth(point);
+            depth < 2 && depth + count >= 2
+        })
+        .count();
+
+    Ok(coverage.count_overlaps() + sloped_overlaps)
+}
+
 struct Parser<'a> {
     lines: Lines<'a>,
 }
@@ -44,8 +94,8 @@ impl<'a> Iterator for Parser<'a> {
                 .map(|parts| {
                     let numbers = parts
                         .split(',')
-                        .map(|number| number.parse::<u16>().ok())
-                        .collect::<Option<Vec<u16>>>()
+                        .map(|number| number.parse::<u32>().ok())
+                        .collect::<Option<Vec<u32>>>()
                         .unwrap();
                     Point::new(*numbers.first().unwrap(), *numbers.last().unwrap())
                 })
@@ -58,27 +108,14 @@ impl<'a> Iterator for Parser<'a> {
 
 #[derive(PartialOrd, PartialEq, Debug, Copy, Clone, Eq, Hash)]
 struct Point {
-    x: u16,
-    y: u16,
+    x: u32,
+    y: u32,
 }
 
 impl Point {
-    fn new(x: u16, y: u16) -> Self {
+    fn new(x: u32, y: u32) -> Self {
         Self { x, y }
     }
-
-    fn advance_towards(self, other: Point) -> Point {
-        let adv = |current: u16, target: u16| match current.cmp(&target) {
-            std::cmp::Ordering::Less => current + 1,
-            std::cmp::Ordering::Equal => current,
-            std::cmp::Ordering::Greater => current - 1,
-        };
-
-        Point {
-            x: adv(self.x, other.x),
-            y: adv(self.y, other.y),
-        }
-    }
 }
 
 #[derive(PartialEq, Debug, Copy, Clone)]
@@ -87,61 +124,123 @@ struct PointRange {
     end: Point,
 }
 
-#[allow(dead_code)]
+#[derive(PartialEq, Debug, Copy, Clone)]
 enum Orientation {
     Horizontal,
     Vertical,
+    /// 45° in either direction.
+    Diagonal,
     Other,
 }
 
+/// How a range that is neither axis-aligned nor diagonal is turned into points.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Rasterization {
+    /// Only the points the line passes through.
+    Exact,
+    /// One point per step along the longer axis, the one nearest to the line.
+    Bresenham,
+}
+
+/// Which lines take part in the count.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum LineFilter {
+    /// Horizontal and vertical lines only.
+    AxisAligned,
+    /// Horizontal, vertical and 45° diagonal lines.
+    WithDiagonals,
+    /// Every line.
+    All,
+}
+
+impl LineFilter {
+    fn accepts(self, range: &PointRange) -> bool {
+        match (self, range.orientation()) {
+            (LineFilter::All, _) => true,
+            (_, Orientation::Horizontal | Orientation::Vertical) => true,
+            (LineFilter::WithDiagonals, Orientation::Diagonal) => true,
+            (_, Orientation::Diagonal | Orientation::Other) => false,
+        }
+    }
+}
+
 impl PointRange {
     fn new(start: Point, end: Point) -> PointRange {
         Self { start, end }
     }
 
-    fn iter(&self) -> PointRangeIterator {
+    fn iter(&self, rasterization: Rasterization) -> PointRangeIterator {
+        let (dx, dy) = self.delta();
+        let (dx, dy) = (dx.unsigned_abs(), dy.unsigned_abs());
+
+        let steps = match (self.orientation(), rasterization) {
+            (Orientation::Other, Rasterization::Exact) => gcd(dx, dy),
+            _ => dx.max(dy),
+        };
+
         PointRangeIterator {
             range: *self,
-            current: Some(self.start),
-            finished: false,
+            steps,
+            step: 0,
         }
     }
 
-    #[allow(dead_code)]
+    fn delta(&self) -> (i64, i64) {
+        (
+            self.end.x as i64 - self.start.x as i64,
+            self.end.y as i64 - self.start.y as i64,
+        )
+    }
+
     fn orientation(&self) -> Orientation {
         if self.start.x == self.end.x {
             Orientation::Vertical
         } else if self.start.y == self.end.y {
             Orientation::Horizontal
+        } else if self.start.x.abs_diff(self.end.x) == self.start.y.abs_diff(self.end.y) {
+            Orientation::Diagonal
         } else {
             Orientation::Other
         }
     }
 }
 
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// Walks a range in `steps` equal steps, rounding each point to the nearest lattice point.
+/// The rounding is exact when `steps` divides both deltas.
 struct PointRangeIterator {
     range: PointRange,
-    current: Option<Point>,
-    finished: bool,
+    steps: u64,
+    step: u64,
 }
 
 impl Iterator for PointRangeIterator {
     type Item = Point;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if let Some(current) = self.current {
-            self.finished = current == self.range.end;
+        if self.step > self.steps {
+            return None;
+        }
 
-            self.current = if self.finished {
-                None
-            } else {
-                Some(current.advance_towards(self.range.end))
-            };
+        let (dx, dy) = self.range.delta();
+        let (step, steps) = (self.step as i64, self.steps.max(1) as i64);
+        let along = |start: u32, delta: i64| {
+            (start as i64 + (2 * step * delta + steps).div_euclid(2 * steps)) as u32
+        };
 
-            Some(current)
-        } else {
-            None
-        }
+        self.step += 1;
+
+        Some(Point::new(
+            along(self.range.start.x, dx),
+            along(self.range.start.y, dy),
+        ))
     }
 }
 
@@ -150,19 +249,10 @@ mod tests {
 
     use crate::*;
 
-    #[test]
-    fn test_point_advance_towards() {
-        let target = Point::new(5, 5);
-
-        assert_eq!(Point::new(1, 5), Point::new(0, 5).advance_towards(target));
-        assert_eq!(Point::new(5, 5), Point::new(4, 5).advance_towards(target));
-        assert_eq!(Point::new(5, 5), Point::new(5, 5).advance_towards(target));
-    }
-
     #[test]
     fn test_horizontal_range_iterator() {
         let range = PointRange::new(Point::new(0, 0), Point::new(5, 0));
-        let mut iter = range.iter();
+        let mut iter = range.iter(Rasterization::Exact);
         assert_eq!(Some(Point::new(0, 0)), iter.next());
         assert_eq!(Some(Point::new(1, 0)), iter.next());
         assert_eq!(Some(Point::new(2, 0)), iter.next());
@@ -176,7 +266,7 @@ mod tests {
     #[test]
     fn test_vertical_range_iterator() {
         let range = PointRange::new(Point::new(0, 0), Point::new(0, 5));
-        let mut iter = range.iter();
+        let mut iter = range.iter(Rasterization::Exact);
         assert_eq!(Some(Point::new(0, 0)), iter.next());
         assert_eq!(Some(Point::new(0, 1)), iter.next());
         assert_eq!(Some(Point::new(0, 2)), iter.next());
@@ -186,4 +276,123 @@ mod tests {
         assert_eq!(None, iter.next());
         assert_eq!(None, iter.next());
     }
+
+    #[test]
+    fn test_diagonal_range_iterator() {
+        let range = PointRange::new(Point::new(3, 0), Point::new(0, 3));
+        let points = [(3, 0), (2, 1), (1, 2), (0, 3)].map(|(x, y)| Point::new(x, y));
+
+        assert_eq!(Orientation::Diagonal, range.orientation());
+        assert!(range.iter(Rasterization::Exact).eq(points));
+        assert!(range.iter(Rasterization::Bresenham).eq(points));
+    }
+
+    #[test]
+    fn test_sloped_range_iterator() {
+        let range = PointRange::new(Point::new(0, 0), Point::new(6, 4));
+        assert_eq!(Orientation::Other, range.orientation());
+
+        let exact = [(0, 0), (3, 2), (6, 4)].map(|(x, y)| Point::new(x, y));
+        assert!(range.iter(Rasterization::Exact).eq(exact));
+
+        let bresenham = [(0, 0), (1, 1), (2, 1), (3, 2), (4, 3), (5, 3), (6, 4)];
+        let bresenham = bresenham.map(|(x, y)| Point::new(x, y));
+        assert!(range.iter(Rasterization::Bresenham).eq(bresenham));
+
+        let reversed = PointRange::new(range.end, range.start);
+        assert!(reversed
+            .iter(Rasterization::Exact)
+            .eq(exact.into_iter().rev()));
+    }
+
+    #[test]
+    fn test_single_point_range_iterator() {
+        let range = PointRange::new(Point::new(2, 2), Point::new(2, 2));
+
+        assert!(range.iter(Rasterization::Exact).eq([Point::new(2, 2)]));
+        assert!(range.iter(Rasterization::Bresenham).eq([Point::new(2, 2)]));
+    }
+
+    const EXAMPLE: &str = "0,9 -> 5,9
+8,0 -> 0,8
+9,4 -> 3,4
+2,2 -> 2,1
+7,0 -> 7,4
+6,4 -> 2,0
+0,9 -> 2,9
+3,4 -> 1,4
+0,0 -> 8,8
+5,5 -> 8,2";
+
+    #[test]
+    fn test_line_filter() {
+        let ranges = Parser::new(EXAMPLE.lines())
+            .chain([PointRange::new(Point::new(0, 0), Point::new(2, 1))])
+            .collect::<Vec<_>>();
+
+        let accepted = |filter: LineFilter| ranges.iter().filter(|r| filter.accepts(r)).count();
+        assert_eq!(6, accepted(LineFilter::AxisAligned));
+        assert_eq!(10, accepted(LineFilter::WithDiagonals));
+        assert_eq!(11, accepted(LineFilter::All));
+    }
+
+    #[test]
+    fn test_count_overlaps() {
+        let count = |ranges: &[PointRange], filter, rasterization| {
+            count_overlaps(ranges, filter, rasterization).unwrap()
+        };
+
+        let ranges = Parser::new(EXAMPLE.lines()).collect::<Vec<_>>();
+        assert_eq!(
+            5,
+            count(&ranges, LineFilter::AxisAligned, Rasterization::Exact)
+        );
+        assert_eq!(
+            12,
+            count(&ranges, LineFilter::WithDiagonals, Rasterization::Exact)
+        );
+        assert_eq!(12, count(&ranges, LineFilter::All, Rasterization::Exact));
+
+        let mut ranges = ranges;
+        ranges.push(PointRange::new(Point::new(0, 0), Point::new(4, 2)));
+        assert_eq!(
+            12,
+            count(&ranges, LineFilter::WithDiagonals, Rasterization::Exact)
+        );
+        assert_eq!(15, count(&ranges, LineFilter::All, Rasterization::Exact));
+        assert_eq!(
+            16,
+            count(&ranges, LineFilter::All, Rasterization::Bresenham)
+        );
+    }
+
+    #[test]
+    fn test_count_overlaps_mixed() {
+        let mut ranges = Parser::new(EXAMPLE.lines()).collect::<Vec<_>>();
+        ranges.extend(
+            [
+                ((0, 0), (4, 2)),
+                ((9, 0), (0, 3)),
+                ((1, 9), (7, 0)),
+                ((2, 0), (8, 9)),
+                ((0, 8), (9, 5)),
+                ((4, 2), (8, 4)),
+            ]
+            .map(|((x1, y1), (x2, y2))| PointRange::new(Point::new(x1, y1), Point::new(x2, y2))),
+        );
+
+        for rasterization in [Rasterization::Exact, Rasterization::Bresenham] {
+            let mut overlaps: HashMap<Point, usize> = HashMap::new();
+            ranges
+                .iter()
+                .flat_map(|r| r.iter(rasterization))
+                .for_each(|p| *overlaps.entry(p).or_insert(0) += 1);
+            let expected = overlaps.values().filter(|&&count| count >= 2).count();
+
+            assert_eq!(
+                expected,
+                count_overlaps(&ranges, LineFilter::All, rasterization).unwrap()
+            );
+        }
+    }
 }
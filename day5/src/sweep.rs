@@ -0,0 +1,359 @@
+//! Counts the points covered by at least two lines from the line ends alone, so the cost
+//! does not grow with the length of the lines.
+//!
+//! Every horizontal, vertical or 45° line lies on a straight line of its family and covers
+//! a range of positions along it. Overlaps of lines on the same straight line are found with
+//! a sweep over the range ends. Lines of different families meet in at most one point, those
+//! meetings are found with a sweep that keeps the lines of one family crossing the sweep
+//! position in an ordered set.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use anyhow::bail;
+
+use crate::{Point, PointRange};
+
+/// Axis-aligned and diagonal ranges as the runs they cover at least once and at least twice
+/// on every straight line, sorted along the line.
+pub struct Coverage {
+    once: HashMap<(Family, i64), Vec<(i64, i64)>>,
+    twice: HashMap<(Family, i64), Vec<(i64, i64)>>,
+}
+
+impl Coverage {
+    /// Fails on ranges that are neither axis-aligned nor 45° diagonals.
+    pub fn new(ranges: &[PointRange]) -> anyhow::Result<Self> {
+        let mut lines: HashMap<(Family, i64), Vec<(i64, i64)>> = HashMap::new();
+        for range in ranges {
+            let (family, key, lo, hi) = Family::classify(range)?;
+            lines.entry((family, key)).or_default().push((lo, hi));
+        }
+
+        let mut coverage = Coverage {
+            once: HashMap::new(),
+            twice: HashMap::new(),
+        };
+        for (line, intervals) in lines {
+            let [once, twice] = runs(&intervals);
+
+            coverage.once.insert(line, once);
+            if !twice.is_empty() {
+                coverage.twice.insert(line, twice);
+            }
+        }
+
+        Ok(coverage)
+    }
+
+    /// The number of points covered at least twice.
+    pub fn count_overlaps(&self) -> usize {
+        let collinear = self
+            .twice
+            .values()
+            .flatten()
+            .map(|(lo, hi)| (hi - lo + 1) as usize)
+            .sum::<usize>();
+
+        let mut crossings = HashSet::new();
+        for (index, &rows) in FAMILIES.iter().enumerate() {
+            for &columns in &FAMILIES[index + 1..] {
+                let along = |family: Family, other: Family| {
+                    self.once
+                        .iter()
+                        .filter(|((line, _), _)| *line == family)
+                        .flat_map(|(&(_, key), runs)| runs.iter().map(move |&run| (key, run)))
+                        .map(|(key, (lo, hi))| {
+                            let (a, b) = (family.point(key, lo), family.point(key, hi));
+                            let (a, b) = (other.locate(a).0, other.locate(b).0);
+                            (key, a.min(b), a.max(b))
+                        })
+                        .collect::<Vec<_>>()
+                };
+
+                for (column, row) in sweep(&along(rows, columns), &along(columns, rows)) {
+                    crossings.extend(intersect((rows, row), (columns, column)));
+                }
+            }
+        }
+
+        // A crossing counts once unless it is already part of the collinear overlaps, where
+        // doubled runs of different families crossing each other were counted more than once.
+        crossings.into_iter().fold(collinear, |total, point| {
+            match self.lines_in(&self.twice, point) {
+                0 => total + 1,
+                families => total - (families - 1),
+            }
+        })
+    }
+
+    /// How many of the ranges cover the point, up to two.
+    pub fn depth(&self, point: Point) -> usize {
+        let point = (point.x as i64, point.y as i64);
+        (self.lines_in(&self.once, point) + self.lines_in(&self.twice, point)).min(2)
+    }
+
+    /// The number of straight lines whose runs contain the point.
+    fn lines_in(&self, runs: &HashMap<(Family, i64), Vec<(i64, i64)>>, point: (i64, i64)) -> usize {
+        FAMILIES
+            .iter()
+            .filter(|&&family| {
+                let (key, t) = family.locate(point);
+                runs.get(&(family, key)).is_some_and(|runs| {
+                    let index = runs.partition_point(|&(_, hi)| hi < t);
+                    runs.get(index).is_some_and(|&(lo, _)| lo <= t)
+                })
+            })
+            .count()
+    }
+}
+
+/// A family of parallel straight lines.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+enum Family {
+    Horizontal,
+    Vertical,
+    /// x and y grow together.
+    Diagonal,
+    /// y falls as x grows.
+    AntiDiagonal,
+}
+
+const FAMILIES: [Family; 4] = [
+    Family::Horizontal,
+    Family::Vertical,
+    Family::Diagonal,
+    Family::AntiDiagonal,
+];
+
+impl Family {
+    /// The family of a range, the straight line it lies on and the positions it covers
+    /// along that line. A single point counts as vertical.
+    fn classify(range: &PointRange) -> anyhow::Result<(Family, i64, i64, i64)> {
+        let start = (range.start.x as i64, range.start.y as i64);
+        let end = (range.end.x as i64, range.end.y as i64);
+        let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+
+        let family = if dx == 0 {
+            Family::Vertical
+        } else if dy == 0 {
+            Family::Horizontal
+        } else if dx == dy {
+            Family::Diagonal
+        } else if dx == -dy {
+            Family::AntiDiagonal
+        } else {
+            bail!("{:?} is neither axis-aligned nor diagonal", range);
+        };
+
+        let (key, a) = family.locate(start);
+        let (_, b) = family.locate(end);
+        Ok((family, key, a.min(b), a.max(b)))
+    }
+
+    /// The straight line of this family through a point and the position of the point on it.
+    fn locate(self, (x, y): (i64, i64)) -> (i64, i64) {
+        match self {
+            Family::Horizontal => (y, x),
+            Family::Vertical => (x, y),
+            Family::Diagonal => (x - y, x),
+            Family::AntiDiagonal => (x + y, x),
+        }
+    }
+
+    fn point(self, key: i64, t: i64) -> (i64, i64) {
+        match self {
+            Family::Horizontal => (t, key),
+            Family::Vertical => (key, t),
+            Family::Diagonal => (t, t - key),
+            Family::AntiDiagonal => (t, key - t),
+        }
+    }
+}
+
+/// The positions covered at least once and at least twice by the intervals, as sorted
+/// disjoint intervals.
+fn runs(intervals: &[(i64, i64)]) -> [Vec<(i64, i64)>; 2] {
+    let mut events = intervals
+        .iter()
+        .flat_map(|&(lo, hi)| [(lo, 1), (hi + 1, -1)])
+        .collect::<Vec<_>>();
+    events.sort_unstable();
+
+    let mut runs = [Vec::new(), Vec::new()];
+    let mut starts = [0; 2];
+    let mut depth = 0;
+
+    for group in events.chunk_by(|a, b| a.0 == b.0) {
+        let position = group[0].0;
+        let before = depth;
+        depth += group.iter().map(|&(_, delta)| delta).sum::<i32>();
+
+        for (level, (runs, start)) in (1..).zip(runs.iter_mut().zip(starts.iter_mut())) {
+            if before < level && depth >= level {
+                *start = position;
+            } else if before >= level && depth < level {
+                runs.push((*start, position - 1));
+            }
+        }
+    }
+
+    runs
+}
+
+/// Crossings of rows `(b, a_lo, a_hi)` and columns `(a, b_lo, b_hi)` as `(a, b)`, found by
+/// sweeping over `a`.
+fn sweep(rows: &[(i64, i64, i64)], columns: &[(i64, i64, i64)]) -> Vec<(i64, i64)> {
+    enum Event {
+        Insert(i64),
+        Query(i64, i64),
+        Remove(i64),
+    }
+
+    let mut events = rows
+        .iter()
+        .flat_map(|&(b, lo, hi)| [(lo, 0, Event::Insert(b)), (hi, 2, Event::Remove(b))])
+        .chain(
+            columns
+                .iter()
+                .map(|&(a, lo, hi)| (a, 1, Event::Query(lo, hi))),
+        )
+        .collect::<Vec<_>>();
+    events.sort_unstable_by_key(|&(position, order, _)| (position, order));
+
+    let mut active: BTreeMap<i64, usize> = BTreeMap::new();
+    let mut crossings = Vec::new();
+
+    for (position, _, event) in events {
+        match event {
+            Event::Insert(b) => *active.entry(b).or_default() += 1,
+            Event::Remove(b) => {
+                if let Some(count) = active.get_mut(&b) {
+                    *count -= 1;
+                    if *count == 0 {
+                        active.remove(&b);
+                    }
+                }
+            }
+            Event::Query(lo, hi) => {
+                crossings.extend(active.range(lo..=hi).map(|(&b, _)| (position, b)));
+            }
+        }
+    }
+
+    crossings
+}
+
+/// The lattice point where straight lines of two different families meet. Diagonals of
+/// both directions meet between lattice points when their keys differ in parity.
+fn intersect((f1, k1): (Family, i64), (f2, k2): (Family, i64)) -> Option<(i64, i64)> {
+    use Family::*;
+
+    match (f1, f2) {
+        (Horizontal, Vertical) => Some((k2, k1)),
+        (Horizontal, Diagonal) => Some((k2 + k1, k1)),
+        (Horizontal, AntiDiagonal) => Some((k2 - k1, k1)),
+        (Vertical, Diagonal) => Some((k1, k1 - k2)),
+        (Vertical, AntiDiagonal) => Some((k1, k2 - k1)),
+        (Diagonal, AntiDiagonal) => ((k1 + k2) % 2 == 0).then_some(((k1 + k2) / 2, (k2 - k1) / 2)),
+        (a, b) if a == b => None,
+        _ => intersect((f2, k2), (f1, k1)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::sweep::*;
+    use crate::{LineFilter, Parser, Point, Rasterization, INPUT};
+
+    fn rasterized(ranges: &[PointRange]) -> usize {
+        let mut overlaps: HashMap<Point, usize> = HashMap::new();
+        ranges
+            .iter()
+            .flat_map(|r| r.iter(Rasterization::Exact))
+            .for_each(|p| *overlaps.entry(p).or_insert(0) += 1);
+
+        overlaps.values().filter(|&&count| count >= 2).count()
+    }
+
+    #[test]
+    fn test_coverage() {
+        let ranges = Parser::new(INPUT.lines()).collect::<Vec<_>>();
+        assert_eq!(
+            rasterized(&ranges),
+            Coverage::new(&ranges).unwrap().count_overlaps()
+        );
+
+        let ranges = ranges
+            .into_iter()
+            .filter(|r| LineFilter::AxisAligned.accepts(r))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            rasterized(&ranges),
+            Coverage::new(&ranges).unwrap().count_overlaps()
+        );
+    }
+
+    #[test]
+    fn test_coverage_random() {
+        // Short lines on a small grid overlap and cross a lot.
+        let mut seed = 0x2545_f491_4f6c_dd1d_u64;
+        let mut next = |bound: u64| {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            (seed % bound) as u32
+        };
+
+        for _ in 0..200 {
+            let ranges = (0..12)
+                .map(|_| {
+                    // Keep y above the length so anti-diagonals stay on the grid.
+                    let start = Point::new(next(12), next(12) + 8);
+                    let length = next(8);
+                    let end = match next(4) {
+                        0 => Point::new(start.x + length, start.y),
+                        1 => Point::new(start.x, start.y + length),
+                        2 => Point::new(start.x + length, start.y + length),
+                        _ => Point::new(start.x + length, start.y - length),
+                    };
+
+                    match next(2) {
+                        0 => PointRange::new(start, end),
+                        _ => PointRange::new(end, start),
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            assert_eq!(
+                rasterized(&ranges),
+                Coverage::new(&ranges).unwrap().count_overlaps()
+            );
+        }
+    }
+
+    #[test]
+    fn test_coverage_long_lines() {
+        let far = 4_000_000_000;
+        let ranges = [
+            PointRange::new(Point::new(0, 7), Point::new(far, 7)),
+            PointRange::new(Point::new(far / 2, 7), Point::new(far - 1, 7)),
+            PointRange::new(Point::new(0, 0), Point::new(far, far)),
+            PointRange::new(Point::new(far, 0), Point::new(0, far)),
+        ];
+
+        // Two overlapping halves, the diagonal crossing the doubled half, the anti-diagonal
+        // crossing the single half and both diagonals crossing in the middle.
+        assert_eq!(
+            far as usize / 2 + 2,
+            Coverage::new(&ranges).unwrap().count_overlaps()
+        );
+    }
+
+    #[test]
+    fn test_coverage_other_slopes() {
+        let ranges = [PointRange::new(Point::new(0, 0), Point::new(2, 1))];
+        assert!(Coverage::new(&ranges).is_err());
+    }
+}